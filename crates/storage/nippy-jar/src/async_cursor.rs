@@ -0,0 +1,105 @@
+use crate::{
+    cursor::to_owned_row, NippyJarCursor, NippyJarError, NippyJarHeader, Row, SharedNippyJar,
+};
+
+/// Asynchronous cursor over a [`SharedNippyJar`].
+///
+/// Every read, including the decompression of the values, runs on the blocking thread pool of
+/// tokio with [`tokio::task::spawn_blocking`], so that page faults on cold data don't block the
+/// async runtime.
+pub struct AsyncNippyJarCursor<H = ()> {
+    /// Jar to read from.
+    jar: SharedNippyJar<H>,
+    /// Cursor row position.
+    row: u64,
+}
+
+impl<H: NippyJarHeader> std::fmt::Debug for AsyncNippyJarCursor<H> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AsyncNippyJarCursor")
+            .field("jar", &self.jar)
+            .field("row", &self.row)
+            .finish()
+    }
+}
+
+impl<H: NippyJarHeader> AsyncNippyJarCursor<H> {
+    /// Creates a new instance of [`AsyncNippyJarCursor`] for the given [`SharedNippyJar`].
+    pub const fn new(jar: SharedNippyJar<H>) -> Self {
+        Self { jar, row: 0 }
+    }
+
+    /// Returns a reference to the related [`SharedNippyJar`].
+    pub const fn jar(&self) -> &SharedNippyJar<H> {
+        &self.jar
+    }
+
+    /// Returns current row index of the cursor
+    pub const fn row_index(&self) -> u64 {
+        self.row
+    }
+
+    /// Moves the cursor to the given row, so the following [`Self::next_row`] returns it.
+    pub const fn seek_to_row(&mut self, row: usize) {
+        self.row = row as u64;
+    }
+
+    /// Returns an owned row by its number.
+    pub async fn row_by_number(&mut self, row: usize) -> Result<Option<Row>, NippyJarError> {
+        self.seek_to_row(row);
+        self.next_row().await
+    }
+
+    /// Returns the current owned row and advances the cursor.
+    pub async fn next_row(&mut self) -> Result<Option<Row>, NippyJarError> {
+        let row = self.row as usize;
+        let result = self
+            .read(move |cursor| cursor.row_by_number(row).map(|row| row.map(to_owned_row)))
+            .await?;
+        if result.is_some() {
+            self.row += 1;
+        }
+        Ok(result)
+    }
+
+    /// Returns an owned row by its number by using a `mask` to only read certain columns from the
+    /// row.
+    pub async fn row_by_number_with_cols(
+        &mut self,
+        row: usize,
+        mask: usize,
+    ) -> Result<Option<Row>, NippyJarError> {
+        self.seek_to_row(row);
+        let result = self
+            .read(move |cursor| {
+                cursor.row_by_number_with_cols(row, mask).map(|row| row.map(to_owned_row))
+            })
+            .await?;
+        if result.is_some() {
+            self.row += 1;
+        }
+        Ok(result)
+    }
+
+    /// Returns the owned rows of the given numbers, in the same order. Rows past the end of the jar
+    /// are `None`.
+    ///
+    /// See [`NippyJarCursor::rows_by_numbers`].
+    pub async fn rows_by_numbers(
+        &self,
+        rows: Vec<usize>,
+    ) -> Result<Vec<Option<Row>>, NippyJarError> {
+        self.read(move |cursor| cursor.rows_by_numbers(&rows)).await
+    }
+
+    /// Runs `f` with a new [`NippyJarCursor`] on the blocking thread pool.
+    async fn read<T: Send + 'static>(
+        &self,
+        f: impl FnOnce(&mut NippyJarCursor<'_, H>) -> Result<T, NippyJarError> + Send + 'static,
+    ) -> Result<T, NippyJarError> {
+        let jar = self.jar.clone();
+        tokio::task::spawn_blocking(move || f(&mut jar.cursor()))
+            .await
+            .map_err(|err| NippyJarError::Internal(Box::new(err)))?
+    }
+}
@@ -0,0 +1,85 @@
+use std::{
+    fmt,
+    ops::{Deref, DerefMut},
+    sync::Arc,
+};
+
+/// Pool of byte buffers which [`NippyJarCursor`](crate::NippyJarCursor)s decompress rows into.
+///
+/// Sharing one pool across the cursors of a server lets it reuse and bound the memory held by
+/// concurrent reads, see
+/// [`NippyJarCursor::with_buffer_pool`](crate::NippyJarCursor::with_buffer_pool).
+pub trait BufferPool: Send + Sync {
+    /// Returns an empty buffer. Its capacity is grown to `capacity` by the caller if needed.
+    fn acquire(&self, capacity: usize) -> Vec<u8>;
+
+    /// Takes back a buffer which is no longer used.
+    fn release(&self, buffer: Vec<u8>);
+}
+
+/// Buffer which is optionally acquired from a [`BufferPool`], and released back to it on drop.
+#[derive(Default)]
+pub(crate) struct PooledBuffer {
+    /// The buffer.
+    buffer: Vec<u8>,
+    /// Pool the buffer belongs to.
+    pool: Option<Arc<dyn BufferPool>>,
+}
+
+impl PooledBuffer {
+    /// Creates a new [`PooledBuffer`] which is not part of a pool.
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self { buffer: Vec::with_capacity(capacity), pool: None }
+    }
+
+    /// Acquires a new [`PooledBuffer`] of at least `capacity` from `pool`.
+    pub(crate) fn acquire(pool: Arc<dyn BufferPool>, capacity: usize) -> Self {
+        let mut buffer = pool.acquire(capacity);
+        buffer.clear();
+        buffer.reserve(capacity);
+        Self { buffer, pool: Some(pool) }
+    }
+}
+
+impl Clone for PooledBuffer {
+    fn clone(&self) -> Self {
+        let capacity = self.buffer.capacity();
+        let mut buffer = match &self.pool {
+            Some(pool) => Self::acquire(Arc::clone(pool), capacity),
+            None => Self::new(capacity),
+        };
+        buffer.extend_from_slice(&self.buffer);
+        buffer
+    }
+}
+
+impl fmt::Debug for PooledBuffer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PooledBuffer")
+            .field("capacity", &self.buffer.capacity())
+            .field("pooled", &self.pool.is_some())
+            .finish()
+    }
+}
+
+impl Deref for PooledBuffer {
+    type Target = Vec<u8>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.buffer
+    }
+}
+
+impl DerefMut for PooledBuffer {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.buffer
+    }
+}
+
+impl Drop for PooledBuffer {
+    fn drop(&mut self) {
+        if let Some(pool) = self.pool.take() {
+            pool.release(std::mem::take(&mut self.buffer));
+        }
+    }
+}
@@ -0,0 +1,73 @@
+use crate::Row;
+use parking_lot::Mutex;
+use schnellru::{ByLength, LruMap};
+use std::{ops::Range, sync::Arc};
+
+/// Size-bounded LRU cache of decompressed rows, keyed by row number.
+///
+/// Cursors with a cache, see
+/// [`NippyJarCursor::with_row_cache`](crate::NippyJarCursor::with_row_cache), serve repeated reads
+/// of hot rows from it without decompressing them again. Cloning the cache is cheap and the clones
+/// share their rows, so it can be shared by all the cursors of a jar.
+#[derive(Clone)]
+pub struct RowCache {
+    /// Cached rows by their number.
+    rows: Arc<Mutex<LruMap<u64, Row>>>,
+}
+
+impl std::fmt::Debug for RowCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RowCache").field("len", &self.len()).finish()
+    }
+}
+
+impl RowCache {
+    /// Creates a new [`RowCache`] holding at most `capacity` rows.
+    pub fn new(capacity: u32) -> Self {
+        Self { rows: Arc::new(Mutex::new(LruMap::new(ByLength::new(capacity)))) }
+    }
+
+    /// Returns the number of cached rows.
+    pub fn len(&self) -> usize {
+        self.rows.lock().len()
+    }
+
+    /// Returns `true` if there are no cached rows.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Removes all cached rows.
+    pub fn clear(&self) {
+        self.rows.lock().clear()
+    }
+
+    /// Appends the columns selected by `mask`, or all of them if `None`, of a cached row to
+    /// `buffer`, and returns their ranges in it.
+    ///
+    /// Returns `None` if the row is not cached.
+    pub(crate) fn get_into(
+        &self,
+        row: u64,
+        mask: Option<usize>,
+        buffer: &mut Vec<u8>,
+    ) -> Option<Vec<Range<usize>>> {
+        let mut rows = self.rows.lock();
+        let values = rows.get(&row)?;
+
+        let mut ranges = Vec::with_capacity(values.len());
+        for (column, value) in values.iter().enumerate() {
+            if mask.is_none_or(|mask| mask & (1 << column) != 0) {
+                let from = buffer.len();
+                buffer.extend_from_slice(value);
+                ranges.push(from..buffer.len());
+            }
+        }
+        Some(ranges)
+    }
+
+    /// Caches a row.
+    pub(crate) fn insert(&self, row: u64, values: Row) {
+        self.rows.lock().insert(row, values);
+    }
+}
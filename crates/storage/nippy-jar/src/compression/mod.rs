@@ -30,7 +30,6 @@ pub trait Compression: Serialize + for<'a> Deserialize<'a> {
         true
     }
 
-    #[cfg(test)]
     /// If required, prepares compression algorithm with an early pass on the data.
     fn prepare_compression(
         &mut self,
@@ -50,6 +49,36 @@ pub enum Compressors {
     Lz4(Lz4),
 }
 
+impl Compressors {
+    /// Appends compressed data from `src` to `dest`, using the compression settings of `column`
+    /// (e.g. its zstd dictionary).
+    ///
+    /// Returns number of bytes written to `dest`.
+    pub fn compress_column_to(
+        &self,
+        column: usize,
+        src: &[u8],
+        dest: &mut Vec<u8>,
+    ) -> Result<usize, NippyJarError> {
+        match self {
+            Self::Zstd(zstd) => zstd.compress_column_to(column, src, dest),
+            Self::Lz4(_) => self.compress_to(src, dest),
+        }
+    }
+
+    /// Compresses data from `src`, using the compression settings of `column`.
+    pub fn compress_column(&self, column: usize, src: &[u8]) -> Result<Vec<u8>, NippyJarError> {
+        match self {
+            Self::Zstd(zstd) => {
+                let mut compressed = Vec::with_capacity(src.len());
+                zstd.compress_column_to(column, src, &mut compressed)?;
+                Ok(compressed)
+            }
+            Self::Lz4(lz4) => lz4.compress(src),
+        }
+    }
+}
+
 impl Compression for Compressors {
     fn decompress_to(&self, value: &[u8], dest: &mut Vec<u8>) -> Result<(), NippyJarError> {
         match self {
@@ -98,7 +127,6 @@ impl Compression for Compressors {
         }
     }
 
-    #[cfg(test)]
     fn prepare_compression(
         &mut self,
         columns: Vec<impl IntoIterator<Item = Vec<u8>>>,
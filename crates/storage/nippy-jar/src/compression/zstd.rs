@@ -4,10 +4,10 @@ use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::{
     fs::File,
     io::{Read, Write},
-    sync::Arc,
+    sync::{Arc, OnceLock},
 };
 use tracing::*;
-use zstd::bulk::Compressor;
+use zstd::{bulk::Compressor, dict::EncoderDictionary};
 pub use zstd::{bulk::Decompressor, dict::DecoderDictionary};
 
 type RawDictionary = Vec<u8>;
@@ -34,9 +34,20 @@ pub struct Zstd {
     pub use_dict: bool,
     /// Max size of a dictionary
     pub(crate) max_dict_size: usize,
+    /// Optional max size of the dictionary of each column, overriding `max_dict_size`. Columns
+    /// with `None` are compressed without a dictionary.
+    ///
+    /// Only used when training dictionaries: opted-out columns are persisted as empty
+    /// dictionaries.
+    #[serde(skip)]
+    pub(crate) column_max_dict_sizes: Option<Vec<Option<usize>>>,
     /// List of column dictionaries.
     #[serde(with = "dictionaries_serde")]
     pub(crate) dictionaries: Option<Arc<ZstdDictionaries<'static>>>,
+    /// Column dictionaries prepared for compression at `level`. Lazily created from
+    /// `dictionaries` on the first compression of a column value.
+    #[serde(skip)]
+    encoder_dictionaries: EncoderDictionaries,
     /// Number of columns to compress.
     columns: usize,
 }
@@ -49,17 +60,100 @@ impl Zstd {
             level: 0,
             use_dict,
             max_dict_size,
+            column_max_dict_sizes: None,
             dictionaries: None,
+            encoder_dictionaries: EncoderDictionaries(OnceLock::new()),
             columns,
         }
     }
 
+    /// Sets the max dictionary size of each column. Columns with `None` won't have a dictionary,
+    /// which is useful for data that doesn't compress well (e.g. hashes).
+    pub fn with_column_dict_sizes(mut self, max_dict_sizes: Vec<Option<usize>>) -> Self {
+        self.max_dict_size = max_dict_sizes.iter().flatten().copied().max().unwrap_or_default();
+        self.column_max_dict_sizes = Some(max_dict_sizes);
+        self
+    }
+
+    /// Returns the max dictionary size of the given column, or `None` if it has opted out of
+    /// dictionaries.
+    pub fn column_max_dict_size(&self, column: usize) -> Option<usize> {
+        match &self.column_max_dict_sizes {
+            Some(sizes) => sizes.get(column).copied().flatten(),
+            None => Some(self.max_dict_size),
+        }
+    }
+
     /// Sets the compression level for the Zstd compression instance.
     pub const fn with_level(mut self, level: i32) -> Self {
         self.level = level;
         self
     }
 
+    /// Returns `true` if the given column opted out of dictionaries.
+    pub(crate) fn is_column_dictionary_disabled(&self, column: usize) -> bool {
+        self.dictionaries
+            .as_ref()
+            .is_some_and(|dictionaries| matches!(dictionaries[column], ZstdDictionary::Disabled))
+    }
+
+    /// Returns the columns which opted out of dictionaries, as configured with
+    /// [`Self::with_column_dict_sizes`] or, once loaded, as persisted with the dictionaries.
+    pub(crate) fn dictionary_disabled_columns(&self) -> Vec<usize> {
+        (0..self.columns)
+            .filter(|&column| match &self.column_max_dict_sizes {
+                Some(_) => self.column_max_dict_size(column).is_none(),
+                None => self.is_column_dictionary_disabled(column),
+            })
+            .collect()
+    }
+
+    /// Returns the prepared dictionary to compress values of the given column with, or `None` if
+    /// it doesn't use one.
+    fn encoder_dictionary(
+        &self,
+        column: usize,
+    ) -> Result<Option<&EncoderDictionary<'static>>, NippyJarError> {
+        if !self.use_dict {
+            return Ok(None)
+        }
+        let Some(dictionaries) = &self.dictionaries else {
+            return Err(NippyJarError::CompressorNotReady)
+        };
+
+        let encoder_dictionaries = self.encoder_dictionaries.get_or_init(|| {
+            dictionaries
+                .iter()
+                .map(|dict| dict.raw().map(|raw| EncoderDictionary::copy(raw, self.level)))
+                .collect()
+        });
+        Ok(encoder_dictionaries.get(column).and_then(Option::as_ref))
+    }
+
+    /// Appends compressed data from `src` to `dest`, using the dictionary of `column` if it has
+    /// one.
+    ///
+    /// Returns number of bytes written to `dest`.
+    pub fn compress_column_to(
+        &self,
+        column: usize,
+        src: &[u8],
+        dest: &mut Vec<u8>,
+    ) -> Result<usize, NippyJarError> {
+        let Some(dictionary) = self.encoder_dictionary(column)? else {
+            return self.compress_to(src, dest)
+        };
+
+        let before = dest.len();
+
+        let mut encoder = zstd::Encoder::with_prepared_dictionary(dest, dictionary)?;
+        encoder.write_all(src)?;
+
+        let dest = encoder.finish()?;
+
+        Ok(dest.len() - before)
+    }
+
     /// Creates a list of [`Decompressor`] if using dictionaries.
     pub fn decompressors(&self) -> Result<Vec<Decompressor<'_>>, NippyJarError> {
         if let Some(dictionaries) = &self.dictionaries {
@@ -189,7 +283,6 @@ impl Compression for Zstd {
         matches!(self.state, ZstdState::Ready)
     }
 
-    #[cfg(test)]
     /// If using it with dictionaries, prepares a dictionary for each column.
     fn prepare_compression(
         &mut self,
@@ -212,9 +305,19 @@ impl Compression for Zstd {
         if columns.len() != self.columns {
             return Err(NippyJarError::ColumnLenMismatch(self.columns, columns.len()))
         }
+        if let Some(sizes) = &self.column_max_dict_sizes {
+            if sizes.len() != self.columns {
+                return Err(NippyJarError::ColumnLenMismatch(self.columns, sizes.len()))
+            }
+        }
 
         let mut dictionaries = Vec::with_capacity(columns.len());
-        for column in columns {
+        for (index, column) in columns.into_iter().enumerate() {
+            let Some(max_dict_size) = self.column_max_dict_size(index) else {
+                dictionaries.push(None);
+                continue
+            };
+
             // ZSTD requires all training data to be continuous in memory, alongside the size of
             // each entry
             let mut sizes = vec![];
@@ -226,12 +329,13 @@ impl Compression for Zstd {
                 })
                 .collect();
 
-            dictionaries.push(zstd::dict::from_continuous(&data, &sizes, self.max_dict_size)?);
+            dictionaries.push(Some(zstd::dict::from_continuous(&data, &sizes, max_dict_size)?));
         }
 
         debug_assert_eq!(dictionaries.len(), self.columns);
 
         self.dictionaries = Some(Arc::new(ZstdDictionaries::new(dictionaries)));
+        self.encoder_dictionaries = EncoderDictionaries::default();
         self.state = ZstdState::Ready;
 
         Ok(())
@@ -277,68 +381,83 @@ impl std::fmt::Debug for ZstdDictionaries<'_> {
 }
 
 impl ZstdDictionaries<'_> {
-    #[cfg(test)]
-    /// Creates [`ZstdDictionaries`].
-    pub(crate) fn new(raw: Vec<RawDictionary>) -> Self {
-        Self(raw.into_iter().map(ZstdDictionary::Raw).collect())
-    }
-
-    /// Loads a list [`RawDictionary`] into a list of [`ZstdDictionary::Loaded`].
-    pub(crate) fn load(raw: Vec<RawDictionary>) -> Self {
+    /// Creates [`ZstdDictionaries`]. Columns without a dictionary are [`ZstdDictionary::Disabled`].
+    pub(crate) fn new(raw: Vec<Option<RawDictionary>>) -> Self {
         Self(
             raw.into_iter()
-                .map(|dict| ZstdDictionary::Loaded(DecoderDictionary::copy(&dict)))
+                .map(|dict| dict.map_or(ZstdDictionary::Disabled, ZstdDictionary::Raw))
                 .collect(),
         )
     }
 
+    /// Loads a list [`RawDictionary`] into a list of [`ZstdDictionary::Loaded`]. Empty
+    /// dictionaries are loaded as [`ZstdDictionary::Disabled`].
+    pub(crate) fn load(raw: Vec<RawDictionary>) -> Self {
+        Self(raw.into_iter().map(ZstdDictionary::load).collect())
+    }
+
     /// Creates a list of decompressors from a list of [`ZstdDictionary::Loaded`].
     pub(crate) fn decompressors(&self) -> Result<Vec<Decompressor<'_>>, NippyJarError> {
-        Ok(self
-            .iter()
-            .flat_map(|dict| {
-                dict.loaded()
-                    .ok_or(NippyJarError::DictionaryNotLoaded)
-                    .map(Decompressor::with_prepared_dictionary)
+        self.iter()
+            .map(|dict| match dict {
+                ZstdDictionary::Disabled => Ok(Decompressor::new()?),
+                _ => Ok(Decompressor::with_prepared_dictionary(
+                    dict.loaded().ok_or(NippyJarError::DictionaryNotLoaded)?,
+                )?),
             })
-            .collect::<Result<Vec<_>, _>>()?)
+            .collect()
     }
 
     /// Creates a list of compressors from a list of [`ZstdDictionary::Raw`].
     pub(crate) fn compressors(&self) -> Result<Vec<Compressor<'_>>, NippyJarError> {
-        Ok(self
-            .iter()
-            .flat_map(|dict| {
-                dict.raw()
-                    .ok_or(NippyJarError::CompressorNotAllowed)
-                    .map(|dict| Compressor::with_dictionary(0, dict))
+        self.iter()
+            .map(|dict| match dict {
+                ZstdDictionary::Disabled => Ok(Compressor::new(0)?),
+                _ => Ok(Compressor::with_dictionary(
+                    0,
+                    dict.raw().ok_or(NippyJarError::CompressorNotAllowed)?,
+                )?),
             })
-            .collect::<Result<Vec<_>, _>>()?)
+            .collect()
     }
 }
 
 /// A Zstd dictionary. It's created and serialized with [`ZstdDictionary::Raw`], and deserialized as
-/// [`ZstdDictionary::Loaded`].
+/// [`ZstdDictionary::Loaded`], which keeps the raw dictionary to compress and serialize with.
+///
+/// Columns which opted out of dictionaries are [`ZstdDictionary::Disabled`], and are serialized as
+/// an empty dictionary.
 pub(crate) enum ZstdDictionary<'a> {
-    #[cfg_attr(not(test), expect(dead_code))]
     Raw(RawDictionary),
-    Loaded(DecoderDictionary<'a>),
+    Loaded(RawDictionary, DecoderDictionary<'a>),
+    Disabled,
 }
 
 impl ZstdDictionary<'_> {
+    /// Loads a [`RawDictionary`] into a [`ZstdDictionary::Loaded`], or
+    /// [`ZstdDictionary::Disabled`] if it's empty.
+    fn load(raw: RawDictionary) -> Self {
+        if raw.is_empty() {
+            Self::Disabled
+        } else {
+            let dict = DecoderDictionary::copy(&raw);
+            Self::Loaded(raw, dict)
+        }
+    }
+
     /// Returns a reference to the expected `RawDictionary`
     pub(crate) const fn raw(&self) -> Option<&RawDictionary> {
         match self {
-            ZstdDictionary::Raw(dict) => Some(dict),
-            ZstdDictionary::Loaded(_) => None,
+            ZstdDictionary::Raw(dict) | ZstdDictionary::Loaded(dict, _) => Some(dict),
+            ZstdDictionary::Disabled => None,
         }
     }
 
     /// Returns a reference to the expected `DecoderDictionary`
     pub(crate) const fn loaded(&self) -> Option<&DecoderDictionary<'_>> {
         match self {
-            ZstdDictionary::Raw(_) => None,
-            ZstdDictionary::Loaded(dict) => Some(dict),
+            ZstdDictionary::Raw(_) | ZstdDictionary::Disabled => None,
+            ZstdDictionary::Loaded(_, dict) => Some(dict),
         }
     }
 }
@@ -348,8 +467,7 @@ impl<'de> Deserialize<'de> for ZstdDictionary<'_> {
     where
         D: Deserializer<'de>,
     {
-        let dict = RawDictionary::deserialize(deserializer)?;
-        Ok(Self::Loaded(DecoderDictionary::copy(&dict)))
+        Ok(Self::load(RawDictionary::deserialize(deserializer)?))
     }
 }
 
@@ -359,8 +477,8 @@ impl Serialize for ZstdDictionary<'_> {
         S: Serializer,
     {
         match self {
-            ZstdDictionary::Raw(r) => r.serialize(serializer),
-            ZstdDictionary::Loaded(_) => unreachable!(),
+            ZstdDictionary::Raw(r) | ZstdDictionary::Loaded(r, _) => r.serialize(serializer),
+            ZstdDictionary::Disabled => RawDictionary::new().serialize(serializer),
         }
     }
 }
@@ -368,11 +486,28 @@ impl Serialize for ZstdDictionary<'_> {
 #[cfg(test)]
 impl PartialEq for ZstdDictionary<'_> {
     fn eq(&self, other: &Self) -> bool {
-        if let (Self::Raw(a), Self::Raw(b)) = (self, &other) {
-            return a == b
-        }
-        unimplemented!(
-            "`DecoderDictionary` can't be compared. So comparison should be done after decompressing a value."
-        );
+        // `DecoderDictionary` can't be compared, but it's always loaded from the raw dictionary.
+        self.raw() == other.raw()
+    }
+}
+
+/// Lazily created list of [`EncoderDictionary`], one per column. Columns without a dictionary are
+/// `None`.
+#[derive(Default, Deref)]
+struct EncoderDictionaries(OnceLock<Vec<Option<EncoderDictionary<'static>>>>);
+
+impl std::fmt::Debug for EncoderDictionaries {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EncoderDictionaries")
+            .field("num", &self.get().map(Vec::len))
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+impl PartialEq for EncoderDictionaries {
+    fn eq(&self, _other: &Self) -> bool {
+        // Derived from the column dictionaries, which are compared instead.
+        true
     }
 }
@@ -1,8 +1,21 @@
+#[cfg(unix)]
+use crate::FileReader;
 use crate::{
+    buffer_pool::PooledBuffer,
     compression::{Compression, Compressors, Zstd},
-    DataReader, NippyJar, NippyJarError, NippyJarHeader, RefRow,
+    BufferPool, DataReader, NippyJar, NippyJarError, NippyJarHeader, RefRow, Row, RowCache,
+    SliceReader,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    io::Read,
+    ops::Range,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Instant,
 };
-use std::{ops::Range, sync::Arc};
 use zstd::bulk::Decompressor;
 
 /// Simple cursor implementation to retrieve data from [`NippyJar`].
@@ -11,11 +24,19 @@ pub struct NippyJarCursor<'a, H = ()> {
     /// [`NippyJar`] which holds most of the required configuration to read from the file.
     jar: &'a NippyJar<H>,
     /// Data and offset reader.
-    reader: Arc<DataReader>,
+    reader: CursorReader<'a>,
     /// Internal buffer to unload data to without reallocating memory on each retrieval.
-    internal_buffer: Vec<u8>,
+    internal_buffer: PooledBuffer,
+    /// Buffer holding the (possibly compressed) value read from a [`FileReader`].
+    read_buffer: Vec<u8>,
     /// Cursor row position.
     row: u64,
+    /// Optional point in time after which reads are aborted.
+    deadline: Option<Instant>,
+    /// Optional flag which aborts reads once set.
+    cancellation: Option<Arc<AtomicBool>>,
+    /// Optional cache of decompressed rows.
+    row_cache: Option<RowCache>,
 }
 
 impl<H: NippyJarHeader> std::fmt::Debug for NippyJarCursor<'_, H> {
@@ -27,14 +48,7 @@ impl<H: NippyJarHeader> std::fmt::Debug for NippyJarCursor<'_, H> {
 impl<'a, H: NippyJarHeader> NippyJarCursor<'a, H> {
     /// Creates a new instance of [`NippyJarCursor`] for the given [`NippyJar`].
     pub fn new(jar: &'a NippyJar<H>) -> Result<Self, NippyJarError> {
-        let max_row_size = jar.max_row_size;
-        Ok(Self {
-            jar,
-            reader: Arc::new(jar.open_data_reader()?),
-            // Makes sure that we have enough buffer capacity to decompress any row of data.
-            internal_buffer: Vec::with_capacity(max_row_size),
-            row: 0,
-        })
+        Ok(Self::with_cursor_reader(jar, CursorReader::Mmap(Arc::new(jar.open_data_reader()?))))
     }
 
     /// Creates a new instance of [`NippyJarCursor`] with the specified [`NippyJar`] and data
@@ -43,14 +57,84 @@ impl<'a, H: NippyJarHeader> NippyJarCursor<'a, H> {
         jar: &'a NippyJar<H>,
         reader: Arc<DataReader>,
     ) -> Result<Self, NippyJarError> {
-        let max_row_size = jar.max_row_size;
-        Ok(Self {
+        Ok(Self::with_cursor_reader(jar, CursorReader::Mmap(reader)))
+    }
+
+    /// Creates a new instance of [`NippyJarCursor`] with the specified [`NippyJar`] and
+    /// [`FileReader`], which reads with positioned reads instead of memory mapping.
+    #[cfg(unix)]
+    pub fn with_file_reader(
+        jar: &'a NippyJar<H>,
+        reader: Arc<FileReader>,
+    ) -> Result<Self, NippyJarError> {
+        Ok(Self::with_cursor_reader(jar, CursorReader::File(reader)))
+    }
+
+    /// Creates a new instance of [`NippyJarCursor`] which reads from the content of the offsets and
+    /// data files provided by the caller, without any file system interaction.
+    pub fn from_parts(
+        jar: &'a NippyJar<H>,
+        offsets: &'a [u8],
+        data: &'a [u8],
+    ) -> Result<Self, NippyJarError> {
+        Ok(Self::with_cursor_reader(jar, CursorReader::Slice(SliceReader::new(offsets, data)?)))
+    }
+
+    pub(crate) fn with_cursor_reader(jar: &'a NippyJar<H>, reader: CursorReader<'a>) -> Self {
+        Self {
             jar,
             reader,
-            // Makes sure that we have enough buffer capacity to decompress any row of data.
-            internal_buffer: Vec::with_capacity(max_row_size),
+            // Allocated on the first read, so a buffer acquired from a pool doesn't replace one.
+            internal_buffer: PooledBuffer::default(),
+            read_buffer: Vec::new(),
             row: 0,
-        })
+            deadline: None,
+            cancellation: None,
+            row_cache: None,
+        }
+    }
+
+    /// Sets a deadline after which any row read fails with [`NippyJarError::DeadlineExceeded`].
+    ///
+    /// The deadline is checked before each row is read, so a single row read is never interrupted.
+    pub const fn with_deadline(mut self, deadline: Instant) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Sets a cache of decompressed rows, which serves repeated reads of the same rows.
+    ///
+    /// Only rows read in full are added to the cache, but reads of any columns are served from it.
+    /// The cache must only be shared with cursors of the same jar.
+    pub fn with_row_cache(mut self, cache: RowCache) -> Self {
+        self.row_cache = Some(cache);
+        self
+    }
+
+    /// Makes the cursor decompress rows into a buffer acquired from `pool`, which is released back
+    /// to it when the cursor is dropped.
+    pub fn with_buffer_pool(mut self, pool: Arc<dyn BufferPool>) -> Self {
+        self.internal_buffer = PooledBuffer::acquire(pool, self.jar.max_row_size);
+        self
+    }
+
+    /// Sets or clears the deadline of the cursor.
+    pub const fn set_deadline(&mut self, deadline: Option<Instant>) {
+        self.deadline = deadline;
+    }
+
+    /// Sets a cancellation flag. Once it's set to `true`, any row read fails with
+    /// [`NippyJarError::Cancelled`], e.g. to abort a long scan on shutdown.
+    ///
+    /// Like the deadline, the flag is checked before each row is read.
+    pub fn with_cancellation(mut self, cancellation: Arc<AtomicBool>) -> Self {
+        self.cancellation = Some(cancellation);
+        self
+    }
+
+    /// Sets or clears the cancellation flag of the cursor.
+    pub fn set_cancellation(&mut self, cancellation: Option<Arc<AtomicBool>>) {
+        self.cancellation = cancellation;
     }
 
     /// Returns a reference to the related [`NippyJar`]
@@ -63,11 +147,57 @@ impl<'a, H: NippyJarHeader> NippyJarCursor<'a, H> {
         self.row
     }
 
+    /// Returns the current position of the cursor, which can be persisted and later restored with
+    /// [`Self::set_position`].
+    pub const fn position(&self) -> CursorPosition {
+        CursorPosition { row: self.row }
+    }
+
+    /// Moves the cursor to a position previously returned by [`Self::position`].
+    pub const fn set_position(&mut self, position: CursorPosition) {
+        self.row = position.row;
+    }
+
     /// Resets cursor to the beginning.
     pub const fn reset(&mut self) {
         self.row = 0;
     }
 
+    /// Moves the cursor to the given row, so the following [`Self::next_row`] returns it.
+    ///
+    /// Seeking past the end of the jar is allowed, in which case no more rows are returned.
+    pub const fn seek_to_row(&mut self, row: usize) {
+        self.row = row as u64;
+    }
+
+    /// Hints that the rows within `range` will be read soon, so that the pages holding their data
+    /// can be read ahead of time. Rows past the end of the jar are ignored.
+    ///
+    /// This doesn't move the cursor, and does nothing if the cursor doesn't read from a
+    /// memory-mapped file.
+    pub fn prefetch_rows(&self, range: Range<usize>) -> Result<(), NippyJarError> {
+        let columns = self.jar.columns;
+        let end = range.end.min(self.jar.rows);
+        self.reader.prefetch(range.start * columns..end * columns)
+    }
+
+    /// Returns the first row of the jar, and leaves the cursor right after it.
+    ///
+    /// Returns `None` if the jar is empty.
+    pub fn first_row(&mut self) -> Result<Option<RefRow<'_>>, NippyJarError> {
+        self.row_by_number(0)
+    }
+
+    /// Returns the last row of the jar, and leaves the cursor right after it.
+    ///
+    /// Returns `None` if the jar is empty.
+    pub fn last_row(&mut self) -> Result<Option<RefRow<'_>>, NippyJarError> {
+        match self.jar.rows.checked_sub(1) {
+            Some(row) => self.row_by_number(row),
+            None => Ok(None),
+        }
+    }
+
     /// Returns a row by its number.
     pub fn row_by_number(&mut self, row: usize) -> Result<Option<RefRow<'_>>, NippyJarError> {
         self.row = row as u64;
@@ -76,30 +206,110 @@ impl<'a, H: NippyJarHeader> NippyJarCursor<'a, H> {
 
     /// Returns the current value and advances the row.
     pub fn next_row(&mut self) -> Result<Option<RefRow<'_>>, NippyJarError> {
-        self.internal_buffer.clear();
+        let Some(row) = self.read_row(None)? else { return Ok(None) };
+        self.row += 1;
 
-        if self.row as usize >= self.jar.rows {
-            // Has reached the end
+        Ok(Some(self.ref_row(row)))
+    }
+
+    /// Reads a row by its number into the caller-provided `values`, one buffer per column, and
+    /// advances the cursor past it.
+    ///
+    /// The buffers are cleared and reused, so reading many rows into the same `values` doesn't
+    /// allocate once they are large enough. Returns `false` and leaves `values` untouched if the
+    /// row is past the end of the jar.
+    pub fn row_by_number_into(
+        &mut self,
+        row: usize,
+        values: &mut Vec<Vec<u8>>,
+    ) -> Result<bool, NippyJarError> {
+        let Some(row) = self.row_by_number(row)? else { return Ok(false) };
+
+        values.resize_with(row.len(), Vec::new);
+        for (buffer, value) in values.iter_mut().zip(row) {
+            buffer.clear();
+            buffer.extend_from_slice(value);
+        }
+
+        Ok(true)
+    }
+
+    /// Moves the cursor one row back and returns it.
+    ///
+    /// The cursor is left on the returned row, so a following [`Self::next_row`] returns it again.
+    /// Returns `None` if the cursor is at the beginning of the jar.
+    pub fn prev_row(&mut self) -> Result<Option<RefRow<'_>>, NippyJarError> {
+        let current = self.row;
+        self.row = self.row.min(self.jar.rows as u64);
+        if self.row == 0 {
             return Ok(None)
         }
+        self.row -= 1;
 
-        let mut row = Vec::with_capacity(self.jar.columns);
+        match self.read_row(None) {
+            Ok(row) => Ok(row.map(|row| self.ref_row(row))),
+            Err(err) => {
+                self.row = current;
+                Err(err)
+            }
+        }
+    }
+
+    /// Returns the owned rows of the given numbers, in the same order. Rows past the end of the jar
+    /// are `None`.
+    ///
+    /// Rows are read in ascending order, so scattered lookups access the data sequentially.
+    pub fn rows_by_numbers(&mut self, rows: &[usize]) -> Result<Vec<Option<Row>>, NippyJarError> {
+        let mut order = (0..rows.len()).collect::<Vec<_>>();
+        order.sort_unstable_by_key(|&index| rows[index]);
 
-        // Retrieve all column values from the row
-        for column in 0..self.jar.columns {
-            self.read_value(column, &mut row)?;
+        let mut result = vec![None; rows.len()];
+        let mut previous: Option<usize> = None;
+        for index in order {
+            result[index] = match previous {
+                // Duplicated row numbers are only read once.
+                Some(previous) if rows[previous] == rows[index] => result[previous].clone(),
+                _ => self.row_by_number(rows[index])?.map(to_owned_row),
+            };
+            previous = Some(index);
         }
 
-        self.row += 1;
+        Ok(result)
+    }
 
-        Ok(Some(
-            row.into_iter()
-                .map(|v| match v {
-                    ValueRange::Mmap(range) => self.reader.data(range),
-                    ValueRange::Internal(range) => &self.internal_buffer[range],
-                })
-                .collect(),
-        ))
+    /// Returns an iterator over the owned rows, from the current row until the end of the jar.
+    ///
+    /// See [`Self::rows_in_range`].
+    pub fn rows(&mut self) -> RowsIter<'_, 'a, H> {
+        self.rows_in_range(self.row as usize..self.jar.rows)
+    }
+
+    /// Returns an iterator over the owned rows within `range`, ignoring the ones past the end of
+    /// the jar.
+    ///
+    /// The iterator can be consumed from both ends, and leaves the cursor right after the last row
+    /// it read. Rows skipped with [`Iterator::nth`], and therefore [`Iterator::step_by`], are not
+    /// read, so sampling every Nth row doesn't scan the whole range.
+    pub fn rows_in_range(&mut self, range: Range<usize>) -> RowsIter<'_, 'a, H> {
+        let range = range.start as u64..range.end.min(self.jar.rows) as u64;
+        RowsIter { cursor: self, range }
+    }
+
+    /// Returns an iterator over the owned values of a single column, from the first row until the
+    /// end of the jar.
+    ///
+    /// Only the values of `column` are read and decompressed. The iterator can be consumed from
+    /// both ends, and leaves the cursor right after the last row it read.
+    pub const fn column_iter(
+        &mut self,
+        column: usize,
+    ) -> Result<ColumnIter<'_, 'a, H>, NippyJarError> {
+        let columns = self.jar.columns;
+        if column >= columns {
+            return Err(NippyJarError::ColumnOutOfBounds { column, columns })
+        }
+        let range = 0..self.jar.rows as u64;
+        Ok(ColumnIter { cursor: self, column, range })
     }
 
     /// Returns a row by its number by using a `mask` to only read certain columns from the row.
@@ -112,35 +322,233 @@ impl<'a, H: NippyJarHeader> NippyJarCursor<'a, H> {
         self.next_row_with_cols(mask)
     }
 
+    /// Returns the value of a single `column` of a row by its number, and advances the cursor past
+    /// the row.
+    ///
+    /// Only the offsets of the value are resolved and only the value itself is decompressed.
+    pub fn value_by_row_col(
+        &mut self,
+        row: usize,
+        column: usize,
+    ) -> Result<Option<&[u8]>, NippyJarError> {
+        let columns = self.jar.columns;
+        if column >= columns {
+            return Err(NippyJarError::ColumnOutOfBounds { column, columns })
+        }
+
+        Ok(self.row_by_number_with_cols(row, 1 << column)?.map(|row| row[0]))
+    }
+
+    /// Returns the number of the row whose `key_column` value equals `key`, by binary searching
+    /// the column.
+    ///
+    /// The values of `key_column` must be unique and sorted in ascending byte order across the
+    /// rows, which lets lookups run without any index in memory at the cost of a logarithmic
+    /// number of reads. The cursor is left in an unspecified position.
+    pub fn row_number_by_sorted_key(
+        &mut self,
+        key_column: usize,
+        key: &[u8],
+    ) -> Result<Option<usize>, NippyJarError> {
+        let columns = self.jar.columns;
+        if key_column >= columns {
+            return Err(NippyJarError::ColumnOutOfBounds { column: key_column, columns })
+        }
+
+        let (mut low, mut high) = (0, self.jar.rows);
+        while low < high {
+            let mid = low + (high - low) / 2;
+            let value = self.value_by_row_col(mid, key_column)?.expect("row is within range");
+            match value.cmp(key) {
+                std::cmp::Ordering::Less => low = mid + 1,
+                std::cmp::Ordering::Greater => high = mid,
+                std::cmp::Ordering::Equal => return Ok(Some(mid)),
+            }
+        }
+        Ok(None)
+    }
+
+    /// Returns the row whose `key_column` value equals `key`, by binary searching the column.
+    ///
+    /// See [`Self::row_number_by_sorted_key`].
+    pub fn row_by_sorted_key(
+        &mut self,
+        key_column: usize,
+        key: &[u8],
+    ) -> Result<Option<RefRow<'_>>, NippyJarError> {
+        match self.row_number_by_sorted_key(key_column, key)? {
+            Some(row) => self.row_by_number(row),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns the byte range within the data file of the stored, possibly compressed, value of a
+    /// single `column` of a row by its number. Returns `None` if the row is past the end of the
+    /// jar.
+    ///
+    /// Only the offsets are read, which lets callers fetch the value with their own I/O, and the
+    /// cursor doesn't move.
+    pub fn value_range(
+        &self,
+        row: usize,
+        column: usize,
+    ) -> Result<Option<Range<usize>>, NippyJarError> {
+        let columns = self.jar.columns;
+        if column >= columns {
+            return Err(NippyJarError::ColumnOutOfBounds { column, columns })
+        }
+        if row >= self.jar.rows {
+            return Ok(None)
+        }
+        self.value_offset_range(row, column).map(Some)
+    }
+
+    /// Returns the stored bytes of a single `column` of a row by its number, without decompressing
+    /// them, and advances the cursor past the row.
+    ///
+    /// The bytes are compressed with [`NippyJar::column_compressor`] of the column, if any, so they
+    /// can be forwarded as they are and decompressed by the receiver.
+    pub fn raw_value_by_row_col(
+        &mut self,
+        row: usize,
+        column: usize,
+    ) -> Result<Option<RawValue<'_>>, NippyJarError> {
+        self.ensure_deadline()?;
+        let Some(range) = self.value_range(row, column)? else { return Ok(None) };
+        self.row = row as u64 + 1;
+
+        let data = self.reader.read(range.clone(), &mut self.read_buffer)?;
+        Ok(Some(RawValue { range, data }))
+    }
+
+    /// Returns a reader over the value of a single `column` of a row by its number, and advances
+    /// the cursor past the row.
+    ///
+    /// Zstd values are decompressed incrementally as they are read, so very large values don't
+    /// need to be held in memory at once. Uncompressed values are read directly from the data, and
+    /// LZ4 values, which can't be decompressed incrementally, are decompressed upfront into the
+    /// internal buffer of the cursor.
+    pub fn value_reader(
+        &mut self,
+        row: usize,
+        column: usize,
+    ) -> Result<Option<ValueReader<'_>>, NippyJarError> {
+        self.ensure_deadline()?;
+        self.clear_internal_buffer();
+
+        let Some(range) = self.value_range(row, column)? else { return Ok(None) };
+        self.row = row as u64 + 1;
+
+        let value = self.reader.read(range, &mut self.read_buffer)?;
+        let reader = match self.jar.column_compressor(column) {
+            None => ValueReaderKind::Slice(value),
+            Some(Compressors::Zstd(z))
+                if z.use_dict && !z.is_column_dictionary_disabled(column) =>
+            {
+                let dictionary = z.dictionaries.as_ref().expect("dictionaries to exist")[column]
+                    .loaded()
+                    .expect("dictionary to be loaded");
+                ValueReaderKind::Zstd(zstd::stream::read::Decoder::with_prepared_dictionary(
+                    value, dictionary,
+                )?)
+            }
+            Some(Compressors::Zstd(_)) => {
+                ValueReaderKind::Zstd(zstd::stream::read::Decoder::with_buffer(value)?)
+            }
+            Some(compression @ Compressors::Lz4(_)) => {
+                compression.decompress_to(value, &mut self.internal_buffer)?;
+                ValueReaderKind::Slice(&self.internal_buffer)
+            }
+        };
+
+        Ok(Some(ValueReader(reader)))
+    }
+
     /// Returns the current value and advances the row.
     ///
     /// Uses a `mask` to only read certain columns from the row.
     pub fn next_row_with_cols(&mut self, mask: usize) -> Result<Option<RefRow<'_>>, NippyJarError> {
-        self.internal_buffer.clear();
+        let Some(row) = self.read_row(Some(mask))? else { return Ok(None) };
+        self.row += 1;
+
+        Ok(Some(self.ref_row(row)))
+    }
+
+    /// Reads the columns of the current row selected by `mask`, or all of them if `None`, without
+    /// moving the cursor.
+    ///
+    /// Returns `None` if the cursor has reached the end.
+    fn read_row(&mut self, mask: Option<usize>) -> Result<Option<Vec<ValueRange>>, NippyJarError> {
+        self.ensure_deadline()?;
+        self.clear_internal_buffer();
 
         if self.row as usize >= self.jar.rows {
             // Has reached the end
             return Ok(None)
         }
 
+        if let Some(cache) = &self.row_cache {
+            if let Some(ranges) = cache.get_into(self.row, mask, &mut self.internal_buffer) {
+                return Ok(Some(ranges.into_iter().map(ValueRange::Internal).collect()))
+            }
+        }
+
         let columns = self.jar.columns;
         let mut row = Vec::with_capacity(columns);
 
         for column in 0..columns {
-            if mask & (1 << column) != 0 {
+            if mask.is_none_or(|mask| mask & (1 << column) != 0) {
                 self.read_value(column, &mut row)?
             }
         }
-        self.row += 1;
 
-        Ok(Some(
-            row.into_iter()
-                .map(|v| match v {
-                    ValueRange::Mmap(range) => self.reader.data(range),
-                    ValueRange::Internal(range) => &self.internal_buffer[range],
-                })
-                .collect(),
-        ))
+        if let Some(cache) = self.row_cache.as_ref().filter(|_| mask.is_none()) {
+            cache.insert(self.row, to_owned_row(self.ref_row(row.clone())));
+        }
+
+        Ok(Some(row))
+    }
+
+    /// Resolves the value ranges of a row read by [`Self::read_row`] into slices.
+    fn ref_row(&self, row: Vec<ValueRange>) -> RefRow<'_> {
+        row.into_iter()
+            .map(|v| match v {
+                ValueRange::Mmap(range) => self.reader.data(range),
+                ValueRange::Internal(range) => &self.internal_buffer[range],
+            })
+            .collect()
+    }
+
+    /// Clears the internal buffer, making sure that it has enough capacity to decompress any row of
+    /// data.
+    fn clear_internal_buffer(&mut self) {
+        self.internal_buffer.clear();
+        self.internal_buffer.reserve(self.jar.max_row_size);
+    }
+
+    /// Returns an error if the deadline of the cursor has passed or it has been cancelled.
+    fn ensure_deadline(&self) -> Result<(), NippyJarError> {
+        if self.deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            return Err(NippyJarError::DeadlineExceeded)
+        }
+        if self.cancellation.as_ref().is_some_and(|cancelled| cancelled.load(Ordering::Relaxed)) {
+            return Err(NippyJarError::Cancelled)
+        }
+        Ok(())
+    }
+
+    /// Returns the range of the (possibly compressed) value of `column` in `row`.
+    fn value_offset_range(&self, row: usize, column: usize) -> Result<Range<usize>, NippyJarError> {
+        // Find out the offset of the column value
+        let offset_pos = row * self.jar.columns + column;
+
+        if self.jar.rows * self.jar.columns == offset_pos + 1 {
+            // It's the last column of the last row
+            Ok(self.reader.offset(offset_pos)? as usize..self.reader.size())
+        } else {
+            let (value_offset, next_value_offset) = self.reader.offsets(offset_pos)?;
+            Ok(value_offset as usize..next_value_offset as usize)
+        }
     }
 
     /// Takes the column index and reads the range value for the corresponding column.
@@ -149,22 +557,13 @@ impl<'a, H: NippyJarHeader> NippyJarCursor<'a, H> {
         column: usize,
         row: &mut Vec<ValueRange>,
     ) -> Result<(), NippyJarError> {
-        // Find out the offset of the column value
-        let offset_pos = self.row as usize * self.jar.columns + column;
-        let value_offset = self.reader.offset(offset_pos)? as usize;
+        let column_offset_range = self.value_offset_range(self.row as usize, column)?;
+        let value = self.reader.read(column_offset_range.clone(), &mut self.read_buffer)?;
 
-        let column_offset_range = if self.jar.rows * self.jar.columns == offset_pos + 1 {
-            // It's the last column of the last row
-            value_offset..self.reader.size()
-        } else {
-            let next_value_offset = self.reader.offset(offset_pos + 1)? as usize;
-            value_offset..next_value_offset
-        };
-
-        if let Some(compression) = self.jar.compressor() {
+        if let Some(compression) = self.jar.column_compressor(column) {
             let from = self.internal_buffer.len();
             match compression {
-                Compressors::Zstd(z) if z.use_dict => {
+                Compressors::Zstd(z) if z.use_dict && !z.is_column_dictionary_disabled(column) => {
                     // If we are here, then for sure we have the necessary dictionaries and they're
                     // loaded (happens during deserialization). Otherwise, there's an issue
                     // somewhere else and we can't recover here anyway.
@@ -174,33 +573,358 @@ impl<'a, H: NippyJarHeader> NippyJarCursor<'a, H> {
                         .expect("dictionary to be loaded");
                     let mut decompressor = Decompressor::with_prepared_dictionary(dictionaries)?;
                     Zstd::decompress_with_dictionary(
-                        self.reader.data(column_offset_range),
+                        value,
                         &mut self.internal_buffer,
                         &mut decompressor,
                     )?;
                 }
                 _ => {
                     // Uses the chosen default decompressor
-                    compression.decompress_to(
-                        self.reader.data(column_offset_range),
-                        &mut self.internal_buffer,
-                    )?;
+                    compression.decompress_to(value, &mut self.internal_buffer)?;
                 }
             }
             let to = self.internal_buffer.len();
 
             row.push(ValueRange::Internal(from..to));
-        } else {
+        } else if self.reader.is_mapped() {
             // Not compressed
             row.push(ValueRange::Mmap(column_offset_range));
+        } else {
+            // Not compressed, but not kept in memory by the reader either
+            let from = self.internal_buffer.len();
+            self.internal_buffer.extend_from_slice(value);
+            row.push(ValueRange::Internal(from..self.internal_buffer.len()));
         }
 
         Ok(())
     }
 }
 
-/// Helper type that stores the range of the decompressed column value either on a `mmap` slice or
-/// on the internal buffer.
+/// Opaque position of a [`NippyJarCursor`] within a jar.
+///
+/// Returned by [`NippyJarCursor::position`], and serializable so that it can be stored as a
+/// checkpoint and restored with [`NippyJarCursor::set_position`], even after a restart.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+pub struct CursorPosition {
+    /// Row the cursor reads next.
+    row: u64,
+}
+
+/// Iterator over the owned rows of a [`NippyJarCursor`].
+///
+/// Created by [`NippyJarCursor::rows`] and [`NippyJarCursor::rows_in_range`]. Iteration stops
+/// after the first error.
+pub struct RowsIter<'c, 'a, H = ()> {
+    /// Cursor used to read the rows.
+    cursor: &'c mut NippyJarCursor<'a, H>,
+    /// Row numbers left to read.
+    range: Range<u64>,
+}
+
+impl<H: NippyJarHeader> std::fmt::Debug for RowsIter<'_, '_, H> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RowsIter")
+            .field("cursor", &self.cursor)
+            .field("range", &self.range)
+            .finish()
+    }
+}
+
+impl<'c, 'a, H: NippyJarHeader> RowsIter<'c, 'a, H> {
+    /// Returns an iterator which yields the number of each row alongside it.
+    pub const fn with_row_numbers(self) -> NumberedRowsIter<'c, 'a, H> {
+        NumberedRowsIter(self)
+    }
+
+    /// Reads an owned row, and stops the iteration on error.
+    fn read(&mut self, row: u64) -> Result<Row, NippyJarError> {
+        let result = self
+            .cursor
+            .row_by_number(row as usize)
+            .map(|row| to_owned_row(row.expect("row is within range")));
+        if result.is_err() {
+            self.range = Default::default();
+        }
+        result
+    }
+}
+
+impl<H: NippyJarHeader> Iterator for RowsIter<'_, '_, H> {
+    type Item = Result<Row, NippyJarError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let row = self.range.next()?;
+        Some(self.read(row))
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        // Skipped rows are never read.
+        let row = self.range.nth(n)?;
+        Some(self.read(row))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.range.size_hint()
+    }
+}
+
+impl<H: NippyJarHeader> DoubleEndedIterator for RowsIter<'_, '_, H> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let row = self.range.next_back()?;
+        Some(self.read(row))
+    }
+
+    fn nth_back(&mut self, n: usize) -> Option<Self::Item> {
+        let row = self.range.nth_back(n)?;
+        Some(self.read(row))
+    }
+}
+
+impl<H: NippyJarHeader> ExactSizeIterator for RowsIter<'_, '_, H> {}
+
+/// Iterator over the owned rows of a [`NippyJarCursor`] and their numbers.
+///
+/// Created by [`RowsIter::with_row_numbers`]. Iteration stops after the first error.
+pub struct NumberedRowsIter<'c, 'a, H = ()>(RowsIter<'c, 'a, H>);
+
+impl<H: NippyJarHeader> std::fmt::Debug for NumberedRowsIter<'_, '_, H> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("NumberedRowsIter").field(&self.0).finish()
+    }
+}
+
+impl<H: NippyJarHeader> Iterator for NumberedRowsIter<'_, '_, H> {
+    type Item = Result<(usize, Row), NippyJarError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let row = self.0.range.next()?;
+        Some(self.0.read(row).map(|values| (row as usize, values)))
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        // Skipped rows are never read.
+        let row = self.0.range.nth(n)?;
+        Some(self.0.read(row).map(|values| (row as usize, values)))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl<H: NippyJarHeader> DoubleEndedIterator for NumberedRowsIter<'_, '_, H> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let row = self.0.range.next_back()?;
+        Some(self.0.read(row).map(|values| (row as usize, values)))
+    }
+
+    fn nth_back(&mut self, n: usize) -> Option<Self::Item> {
+        let row = self.0.range.nth_back(n)?;
+        Some(self.0.read(row).map(|values| (row as usize, values)))
+    }
+}
+
+impl<H: NippyJarHeader> ExactSizeIterator for NumberedRowsIter<'_, '_, H> {}
+
+/// Iterator over the owned values of a single column of a [`NippyJarCursor`].
+///
+/// Created by [`NippyJarCursor::column_iter`]. Iteration stops after the first error.
+pub struct ColumnIter<'c, 'a, H = ()> {
+    /// Cursor used to read the values.
+    cursor: &'c mut NippyJarCursor<'a, H>,
+    /// Column to read.
+    column: usize,
+    /// Row numbers left to read.
+    range: Range<u64>,
+}
+
+impl<H: NippyJarHeader> std::fmt::Debug for ColumnIter<'_, '_, H> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ColumnIter")
+            .field("cursor", &self.cursor)
+            .field("column", &self.column)
+            .field("range", &self.range)
+            .finish()
+    }
+}
+
+impl<H: NippyJarHeader> ColumnIter<'_, '_, H> {
+    /// Reads an owned value, and stops the iteration on error.
+    fn read(&mut self, row: u64) -> Result<Vec<u8>, NippyJarError> {
+        let result = self
+            .cursor
+            .value_by_row_col(row as usize, self.column)
+            .map(|value| value.expect("row is within range").to_vec());
+        if result.is_err() {
+            self.range = Default::default();
+        }
+        result
+    }
+}
+
+impl<H: NippyJarHeader> Iterator for ColumnIter<'_, '_, H> {
+    type Item = Result<Vec<u8>, NippyJarError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let row = self.range.next()?;
+        Some(self.read(row))
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        // Skipped rows are never read.
+        let row = self.range.nth(n)?;
+        Some(self.read(row))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.range.size_hint()
+    }
+}
+
+impl<H: NippyJarHeader> DoubleEndedIterator for ColumnIter<'_, '_, H> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let row = self.range.next_back()?;
+        Some(self.read(row))
+    }
+
+    fn nth_back(&mut self, n: usize) -> Option<Self::Item> {
+        let row = self.range.nth_back(n)?;
+        Some(self.read(row))
+    }
+}
+
+impl<H: NippyJarHeader> ExactSizeIterator for ColumnIter<'_, '_, H> {}
+
+/// Stored bytes of a column value, as returned by [`NippyJarCursor::raw_value_by_row_col`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RawValue<'a> {
+    /// Range of the value within the data file.
+    pub range: Range<usize>,
+    /// Stored, possibly compressed, bytes of the value.
+    pub data: &'a [u8],
+}
+
+/// [`Read`] implementation over a single column value.
+///
+/// Created by [`NippyJarCursor::value_reader`].
+pub struct ValueReader<'a>(ValueReaderKind<'a>);
+
+impl std::fmt::Debug for ValueReader<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("ValueReader").finish_non_exhaustive()
+    }
+}
+
+impl Read for ValueReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        match &mut self.0 {
+            ValueReaderKind::Slice(value) => value.read(buf),
+            ValueReaderKind::Zstd(decoder) => decoder.read(buf),
+        }
+    }
+}
+
+/// Source of the bytes of a [`ValueReader`].
+enum ValueReaderKind<'a> {
+    /// Uncompressed or already decompressed value.
+    Slice(&'a [u8]),
+    /// Zstd compressed value, decompressed as it's read.
+    Zstd(zstd::stream::read::Decoder<'a, &'a [u8]>),
+}
+
+/// Copies the column values of a [`RefRow`] into a [`Row`].
+pub(crate) fn to_owned_row(row: RefRow<'_>) -> Row {
+    row.into_iter().map(<[u8]>::to_vec).collect()
+}
+
+/// Source of the data and offsets read by [`NippyJarCursor`].
+#[derive(Debug, Clone)]
+pub(crate) enum CursorReader<'a> {
+    /// Memory-mapped data and offsets files.
+    Mmap(Arc<DataReader>),
+    /// Caller-provided data and offsets.
+    Slice(SliceReader<'a>),
+    /// Data and offsets files read with positioned reads.
+    #[cfg(unix)]
+    File(Arc<FileReader>),
+}
+
+impl CursorReader<'_> {
+    fn offset(&self, index: usize) -> Result<u64, NippyJarError> {
+        match self {
+            Self::Mmap(reader) => reader.offset(index),
+            Self::Slice(reader) => reader.offset(index),
+            #[cfg(unix)]
+            Self::File(reader) => reader.offset(index),
+        }
+    }
+
+    /// Returns the offsets for the requested data index and the next one.
+    fn offsets(&self, index: usize) -> Result<(u64, u64), NippyJarError> {
+        match self {
+            #[cfg(unix)]
+            Self::File(reader) => reader.offsets(index),
+            _ => Ok((self.offset(index)?, self.offset(index + 1)?)),
+        }
+    }
+
+    /// Returns `true` if the data stays in memory, so it can be referenced with [`Self::data`].
+    const fn is_mapped(&self) -> bool {
+        match self {
+            Self::Mmap(_) | Self::Slice(_) => true,
+            #[cfg(unix)]
+            Self::File(_) => false,
+        }
+    }
+
+    /// Returns the data within `range`. Only available if [`Self::is_mapped`].
+    fn data(&self, range: Range<usize>) -> &[u8] {
+        match self {
+            Self::Mmap(reader) => reader.data(range),
+            Self::Slice(reader) => reader.data(range),
+            #[cfg(unix)]
+            Self::File(_) => unreachable!("data read from files is copied to the internal buffer"),
+        }
+    }
+
+    /// Returns the data within `range`, using `buffer` to hold it if it isn't kept in memory.
+    fn read<'b>(
+        &'b self,
+        range: Range<usize>,
+        buffer: &'b mut Vec<u8>,
+    ) -> Result<&'b [u8], NippyJarError> {
+        match self {
+            #[cfg(unix)]
+            Self::File(reader) => {
+                reader.data(range, buffer)?;
+                Ok(buffer)
+            }
+            _ => Ok(self.data(range)),
+        }
+    }
+
+    fn size(&self) -> usize {
+        match self {
+            Self::Mmap(reader) => reader.size(),
+            Self::Slice(reader) => reader.size(),
+            #[cfg(unix)]
+            Self::File(reader) => reader.size(),
+        }
+    }
+
+    fn prefetch(&self, indexes: Range<usize>) -> Result<(), NippyJarError> {
+        match self {
+            Self::Mmap(reader) => reader.prefetch(indexes),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Helper type that stores the range of the decompressed column value either on the reader data
+/// (e.g. a `mmap` slice) or on the internal buffer.
+#[derive(Clone)]
 enum ValueRange {
     Mmap(Range<usize>),
     Internal(Range<usize>),
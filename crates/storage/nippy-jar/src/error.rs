@@ -44,6 +44,21 @@ pub enum NippyJarError {
     #[error("unexpected missing value: row:col {0}:{1}")]
     UnexpectedMissingValue(u64, u64),
 
+    /// A column iterator yielded a value past the last row to write, at a specific row and column.
+    #[error("unexpected extra value: row:col {0}:{1}")]
+    UnexpectedExtraValue(u64, u64),
+
+    /// A column iterator yielded an error instead of the value at a specific row and column.
+    #[error("failed to get value: row:col {row}:{column}: {source}")]
+    ColumnValue {
+        /// The row of the value.
+        row: u64,
+        /// The column of the value, which is also the index of the failing iterator.
+        column: u64,
+        /// The error yielded by the iterator.
+        source: Box<dyn core::error::Error + Send + Sync>,
+    },
+
     /// The size of an offset exceeds the maximum allowed size of 8 bytes.
     #[error("the size of an offset must be at most 8 bytes, got {offset_size}")]
     OffsetSizeTooBig {
@@ -89,6 +104,34 @@ pub enum NippyJarError {
     #[error("File is in an inconsistent state.")]
     InconsistentState,
 
+    /// The deadline of a cursor has been exceeded.
+    #[error("deadline exceeded")]
+    DeadlineExceeded,
+
+    /// A read has been cancelled.
+    #[error("read cancelled")]
+    Cancelled,
+
+    /// A column index is not smaller than the number of columns of the jar.
+    #[error("column {column} is out of bounds for {columns} columns")]
+    ColumnOutOfBounds {
+        /// The requested column.
+        column: usize,
+        /// The number of columns of the jar.
+        columns: usize,
+    },
+
+    /// The data of a row doesn't match its checksum.
+    #[error("checksum mismatch at row {row}")]
+    ChecksumMismatch {
+        /// The row whose data doesn't match its checksum, or which has no checksum.
+        row: usize,
+    },
+
+    /// A file is locked by another reader or writer.
+    #[error("file is locked by another reader or writer")]
+    FileLocked,
+
     /// A specified file is missing.
     #[error("Missing file: {}", .0.display())]
     MissingFile(PathBuf),
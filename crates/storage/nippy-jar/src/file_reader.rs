@@ -0,0 +1,182 @@
+use crate::{read_offset_at, read_offset_size, NippyJarError, OFFSETS_FILE_EXTENSION};
+use std::{
+    fs::{File, OpenOptions},
+    ops::Range,
+    os::unix::fs::FileExt,
+    path::Path,
+};
+
+/// Alignment of the file positions, lengths and buffers of direct I/O reads.
+const DIRECT_IO_ALIGNMENT: usize = 4096;
+
+/// Reads the data and offsets files of a static file with positioned reads (`pread`) instead of
+/// memory mapping them.
+///
+/// With direct I/O, see [`Self::new_direct`], reads bypass the page cache, so scanning large
+/// static files doesn't evict other data from it. Every value read is then served from disk.
+#[derive(Debug)]
+pub struct FileReader {
+    /// Data file descriptor.
+    data_file: File,
+    /// Size of the data file.
+    data_size: usize,
+    /// Offset file descriptor.
+    offset_file: File,
+    /// Size of the offsets file.
+    offsets_size: usize,
+    /// Number of bytes that represent one offset.
+    offset_size: u8,
+    /// Whether the files were opened with `O_DIRECT`.
+    direct: bool,
+}
+
+impl FileReader {
+    /// Opens the respective data and offsets files and returns [`FileReader`].
+    pub fn new(path: impl AsRef<Path>) -> Result<Self, NippyJarError> {
+        Self::open(path.as_ref(), OpenOptions::new().read(true), false)
+    }
+
+    /// Opens the respective data and offsets files with `O_DIRECT` and returns [`FileReader`].
+    ///
+    /// Fails if the file system doesn't support direct I/O.
+    #[cfg(target_os = "linux")]
+    pub fn new_direct(path: impl AsRef<Path>) -> Result<Self, NippyJarError> {
+        use std::os::unix::fs::OpenOptionsExt;
+
+        Self::open(path.as_ref(), OpenOptions::new().read(true).custom_flags(libc::O_DIRECT), true)
+    }
+
+    fn open(path: &Path, options: &OpenOptions, direct: bool) -> Result<Self, NippyJarError> {
+        let data_file = options.open(path)?;
+        let data_size = data_file.metadata()?.len() as usize;
+
+        let offset_file = options.open(path.with_extension(OFFSETS_FILE_EXTENSION))?;
+        let offsets_size = offset_file.metadata()?.len() as usize;
+
+        let mut reader =
+            Self { data_file, data_size, offset_file, offsets_size, offset_size: 0, direct };
+
+        let mut buffer = Vec::new();
+        reader.read_at(&reader.offset_file, 0..offsets_size.min(1), &mut buffer)?;
+        reader.offset_size = read_offset_size(&buffer)?;
+
+        Ok(reader)
+    }
+
+    /// Returns the offset for the requested data index
+    pub fn offset(&self, index: usize) -> Result<u64, NippyJarError> {
+        self.read_offsets::<1>(index).map(|[offset]| offset)
+    }
+
+    /// Returns the offsets for the requested data index and the next one, which delimit its value,
+    /// with a single read.
+    pub fn offsets(&self, index: usize) -> Result<(u64, u64), NippyJarError> {
+        self.read_offsets::<2>(index).map(Into::into)
+    }
+
+    /// Reads `N` adjacent offsets, starting at the requested data index, with a single read into
+    /// a stack buffer. `N` can be at most 2.
+    fn read_offsets<const N: usize>(&self, index: usize) -> Result<[u64; N], NippyJarError> {
+        const { assert!(N > 0 && N <= 2) };
+
+        let offset_size = self.offset_size as usize;
+        // + 1 represents the offset_len u8 which is in the beginning of the file
+        let from = index * offset_size + 1;
+        let to = from + N * offset_size;
+        if to > self.offsets_size {
+            let missing = (self.offsets_size.saturating_sub(from) / offset_size).min(N - 1);
+            return Err(NippyJarError::OffsetOutOfBounds { index: from + missing * offset_size })
+        }
+
+        // Two offsets of at most 8 bytes span at most two aligned blocks, plus the alignment of
+        // the buffer itself.
+        let mut buffer = [0u8; 16];
+        let mut aligned_buffer;
+        let buffer = if self.direct {
+            aligned_buffer = [0u8; 3 * DIRECT_IO_ALIGNMENT];
+            &mut aligned_buffer[..]
+        } else {
+            &mut buffer[..]
+        };
+        let bytes = self.read_into(&self.offset_file, from..to, buffer)?;
+
+        let mut offsets = [0; N];
+        for (i, offset) in offsets.iter_mut().enumerate() {
+            *offset = read_offset_at(&buffer[bytes.clone()], self.offset_size, i * offset_size)?;
+        }
+        Ok(offsets)
+    }
+
+    /// Returns number of bytes that represent one offset.
+    pub const fn offset_size(&self) -> u8 {
+        self.offset_size
+    }
+
+    /// Reads the data within `range` into `buffer`, replacing its content.
+    pub fn data(&self, range: Range<usize>, buffer: &mut Vec<u8>) -> Result<(), NippyJarError> {
+        self.read_at(&self.data_file, range, buffer)
+    }
+
+    /// Returns total size of data
+    pub const fn size(&self) -> usize {
+        self.data_size
+    }
+
+    /// Reads the bytes of `file` within `range` into `buffer`, replacing its content.
+    fn read_at(
+        &self,
+        file: &File,
+        range: Range<usize>,
+        buffer: &mut Vec<u8>,
+    ) -> Result<(), NippyJarError> {
+        buffer.clear();
+        buffer.resize(self.buffer_len(&range), 0);
+
+        let bytes = self.read_into(file, range, buffer)?;
+        buffer.copy_within(bytes.clone(), 0);
+        buffer.truncate(bytes.len());
+        Ok(())
+    }
+
+    /// Returns the length of the buffer needed by [`Self::read_into`] to read `range`.
+    const fn buffer_len(&self, range: &Range<usize>) -> usize {
+        if !self.direct {
+            return range.end - range.start
+        }
+        let start = range.start - range.start % DIRECT_IO_ALIGNMENT;
+        range.end.next_multiple_of(DIRECT_IO_ALIGNMENT) - start + DIRECT_IO_ALIGNMENT
+    }
+
+    /// Reads the bytes of `file` within `range` into `buffer`, which has to be at least
+    /// [`Self::buffer_len`] long. Returns the position of the bytes within `buffer`.
+    fn read_into(
+        &self,
+        file: &File,
+        range: Range<usize>,
+        buffer: &mut [u8],
+    ) -> Result<Range<usize>, NippyJarError> {
+        if !self.direct {
+            file.read_exact_at(&mut buffer[..range.len()], range.start as u64)?;
+            return Ok(0..range.len())
+        }
+
+        // Direct reads must start and end at aligned file positions, and write to an aligned
+        // buffer, so the enclosing aligned blocks are read.
+        let start = range.start - range.start % DIRECT_IO_ALIGNMENT;
+        let end = range.end.next_multiple_of(DIRECT_IO_ALIGNMENT);
+        let shift = buffer.as_ptr().align_offset(DIRECT_IO_ALIGNMENT);
+
+        // The last block can be short if it reaches the end of the file.
+        let mut read = 0;
+        while start + read < range.end {
+            let block = &mut buffer[shift + read..shift + end - start];
+            match file.read_at(block, (start + read) as u64)? {
+                0 => return Err(std::io::Error::from(std::io::ErrorKind::UnexpectedEof).into()),
+                n => read += n,
+            }
+        }
+
+        let from = shift + range.start - start;
+        Ok(from..from + range.len())
+    }
+}
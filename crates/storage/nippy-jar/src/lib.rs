@@ -12,12 +12,14 @@
 #![cfg_attr(not(test), warn(unused_crate_dependencies))]
 #![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
 
-use memmap2::Mmap;
-use serde::{Deserialize, Serialize};
+use memmap2::{Mmap, MmapOptions};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::{
     error::Error as StdError,
     fs::File,
-    io::Read,
+    io::{Read, Write},
+    num::NonZeroUsize,
     ops::Range,
     path::{Path, PathBuf},
 };
@@ -25,9 +27,7 @@ use tracing::*;
 
 /// Compression algorithms supported by `NippyJar`.
 pub mod compression;
-#[cfg(test)]
-use compression::Compression;
-use compression::Compressors;
+use compression::{Compression, Compressors};
 
 /// empty enum for backwards compatibility
 #[derive(Debug, Serialize, Deserialize)]
@@ -43,10 +43,38 @@ mod error;
 pub use error::NippyJarError;
 
 mod cursor;
-pub use cursor::NippyJarCursor;
+pub use cursor::{
+    ColumnIter, CursorPosition, NippyJarCursor, NumberedRowsIter, RawValue, RowsIter, ValueReader,
+};
+
+mod buffer_pool;
+pub use buffer_pool::BufferPool;
+
+mod cache;
+pub use cache::RowCache;
+
+mod shared;
+pub use shared::SharedNippyJar;
+
+#[cfg(unix)]
+mod lock;
+
+#[cfg(unix)]
+mod file_reader;
+#[cfg(unix)]
+pub use file_reader::FileReader;
+
+#[cfg(feature = "tokio")]
+mod async_cursor;
+#[cfg(feature = "tokio")]
+pub use async_cursor::AsyncNippyJarCursor;
+
+mod typed;
+pub use typed::{RowDecode, TypedCursor};
 
 mod writer;
-pub use writer::NippyJarWriter;
+pub use writer::{NippyJarWriter, WriteProgress};
+use writer::{ValueWriter, CHECKSUM_SIZE_BYTES, OFFSET_SIZE_BYTES};
 
 mod consistency;
 pub use consistency::NippyJarChecker;
@@ -59,11 +87,16 @@ const INDEX_FILE_EXTENSION: &str = "idx";
 const OFFSETS_FILE_EXTENSION: &str = "off";
 /// The file extension used for configuration files.
 pub const CONFIG_FILE_EXTENSION: &str = "conf";
+/// The file extension used for checksums files.
+const CHECKSUMS_FILE_EXTENSION: &str = "sum";
 
 /// A [`RefRow`] is a list of column value slices pointing to either an internal buffer or a
 /// memory-mapped file.
 type RefRow<'a> = Vec<&'a [u8]>;
 
+/// A [`Row`] is a list of owned column values.
+type Row = Vec<Vec<u8>>;
+
 /// Alias type for a column value wrapped in `Result`.
 pub type ColumnResult<T> = Result<T, Box<dyn StdError + Send + Sync>>;
 
@@ -106,6 +139,12 @@ pub struct NippyJar<H = ()> {
     /// Maximum uncompressed row size of the set. This will enable decompression without any
     /// resizing of the output buffer.
     max_row_size: usize,
+    /// Optional mask of the columns which are stored without compression, since their values are
+    /// already compressed (or encrypted).
+    ///
+    /// Appended to the original layout, so it's read separately by [`Self::load_from_reader`].
+    #[serde(skip_serializing_if = "Option::is_none", skip_deserializing)]
+    raw_columns: Option<usize>,
     /// Data path for file. Supporting files will have a format `{path}.{extension}`.
     #[serde(skip)]
     path: PathBuf,
@@ -123,6 +162,7 @@ impl<H: NippyJarHeader> std::fmt::Debug for NippyJar<H> {
             .field("phf", &self.phf)
             .field("path", &self.path)
             .field("max_row_size", &self.max_row_size)
+            .field("raw_columns", &self.raw_columns)
             .finish_non_exhaustive()
     }
 }
@@ -149,6 +189,7 @@ impl<H: NippyJarHeader> NippyJar<H> {
             rows: 0,
             max_row_size: 0,
             compressor: None,
+            raw_columns: None,
             filter: None,
             phf: None,
             path: path.to_path_buf(),
@@ -162,12 +203,30 @@ impl<H: NippyJarHeader> NippyJar<H> {
         self
     }
 
+    /// Adds [`compression::Zstd`] compression with a max dictionary size per column. Columns with
+    /// `None` are compressed without a dictionary.
+    ///
+    /// The dictionaries need to be trained with [`Self::prepare_compression`] before writing.
+    pub fn with_zstd_column_dicts(mut self, max_dict_sizes: Vec<Option<usize>>) -> Self {
+        self.compressor = Some(Compressors::Zstd(
+            compression::Zstd::new(true, 0, self.columns).with_column_dict_sizes(max_dict_sizes),
+        ));
+        self
+    }
+
     /// Adds [`compression::Lz4`] compression.
     pub fn with_lz4(mut self) -> Self {
         self.compressor = Some(Compressors::Lz4(compression::Lz4::default()));
         self
     }
 
+    /// Stores the columns of the given mask without compression. Compressing values which are
+    /// already compressed (or encrypted) only wastes CPU.
+    pub const fn with_raw_columns(mut self, mask: usize) -> Self {
+        self.raw_columns = Some(mask);
+        self
+    }
+
     /// Gets a reference to the user header.
     pub const fn user_header(&self) -> &H {
         &self.user_header
@@ -188,11 +247,45 @@ impl<H: NippyJarHeader> NippyJar<H> {
         self.compressor.as_ref()
     }
 
+    /// Returns `true` if the column is stored without compression.
+    pub fn is_raw_column(&self, column: usize) -> bool {
+        self.raw_columns.is_some_and(|mask| mask & (1 << column) != 0)
+    }
+
+    /// Gets a reference to the compressor of the given column, if it's compressed.
+    pub fn column_compressor(&self, column: usize) -> Option<&Compressors> {
+        self.compressor.as_ref().filter(|_| !self.is_raw_column(column))
+    }
+
     /// Gets a mutable reference to the compressor.
     pub const fn compressor_mut(&mut self) -> Option<&mut Compressors> {
         self.compressor.as_mut()
     }
 
+    /// Returns a stable digest of the effective configuration of the jar: format version, number of
+    /// columns, compression settings and raw columns. The `NippyJar` format has no inclusion
+    /// filter or perfect hashing function anymore, so there's nothing else to cover.
+    ///
+    /// Data dependent state (row count, user header, trained dictionaries) is not part of it, so
+    /// jars created with the same settings share the same digest.
+    pub fn config_hash(&self) -> Result<[u8; 32], NippyJarError> {
+        // (kind, level, use_dict, max_dict_size, dictionary_disabled_columns)
+        let compressor = self.compressor.as_ref().map(|compressor| match compressor {
+            Compressors::Zstd(zstd) => (
+                0u8,
+                zstd.level,
+                zstd.use_dict,
+                zstd.max_dict_size,
+                zstd.dictionary_disabled_columns(),
+            ),
+            Compressors::Lz4(_) => (1u8, 0, false, 0, vec![]),
+        });
+        let config =
+            bincode::serialize(&(self.version, self.columns, compressor, self.raw_columns))?;
+
+        Ok(Sha256::digest(config).into())
+    }
+
     /// Loads the file configuration and returns [`Self`].
     ///
     /// **The user must ensure the header type matches the one used during the jar's creation.**
@@ -208,8 +301,16 @@ impl<H: NippyJarHeader> NippyJar<H> {
     }
 
     /// Deserializes an instance of [`Self`] from a [`Read`] type.
-    pub fn load_from_reader<R: Read>(reader: R) -> Result<Self, NippyJarError> {
-        Ok(bincode::deserialize_from(reader)?)
+    pub fn load_from_reader<R: Read>(mut reader: R) -> Result<Self, NippyJarError> {
+        let mut jar: Self = bincode::deserialize_from(&mut reader)?;
+        jar.raw_columns = read_appended_field(reader)?;
+        Ok(jar)
+    }
+
+    /// Serializes the configuration into `writer`, in the format read by
+    /// [`Self::load_from_reader`].
+    pub fn save_to_writer<W: Write>(&self, writer: W) -> Result<(), NippyJarError> {
+        Ok(bincode::serialize_into(writer, self)?)
     }
 
     /// Returns the path for the data file
@@ -232,13 +333,51 @@ impl<H: NippyJarHeader> NippyJar<H> {
         self.path.with_extension(CONFIG_FILE_EXTENSION)
     }
 
+    /// Returns the path for the checksums file
+    pub fn checksums_path(&self) -> PathBuf {
+        self.path.with_extension(CHECKSUMS_FILE_EXTENSION)
+    }
+
+    /// Verifies the data of every row against the checksums written by a [`NippyJarWriter`] with
+    /// checksums enabled.
+    ///
+    /// Fails with [`NippyJarError::ChecksumMismatch`] at the first row whose data doesn't match its
+    /// checksum, or which has no checksum.
+    pub fn verify_checksums(&self) -> Result<(), NippyJarError> {
+        let checksums_path = self.checksums_path();
+        if !checksums_path.exists() {
+            return Err(NippyJarError::MissingFile(checksums_path))
+        }
+        let checksums = reth_fs_util::read(checksums_path)?;
+        let reader = self.open_data_reader()?;
+
+        for row in 0..self.rows {
+            let start = row * CHECKSUM_SIZE_BYTES as usize;
+            let expected = checksums
+                .get(start..start + CHECKSUM_SIZE_BYTES as usize)
+                .ok_or(NippyJarError::ChecksumMismatch { row })?;
+
+            let data_start = reader.offset(row * self.columns)? as usize;
+            let data_end = reader.offset((row + 1) * self.columns)? as usize;
+            if crc32fast::hash(reader.data(data_start..data_end)).to_le_bytes() != expected {
+                return Err(NippyJarError::ChecksumMismatch { row })
+            }
+        }
+
+        Ok(())
+    }
+
     /// Deletes from disk this [`NippyJar`] alongside every satellite file.
     pub fn delete(self) -> Result<(), NippyJarError> {
         // TODO(joshie): ensure consistency on unexpected shutdown
 
-        for path in
-            [self.data_path().into(), self.index_path(), self.offsets_path(), self.config_path()]
-        {
+        for path in [
+            self.data_path().into(),
+            self.index_path(),
+            self.offsets_path(),
+            self.config_path(),
+            self.checksums_path(),
+        ] {
             if path.exists() {
                 debug!(target: "nippy-jar", ?path, "Removing file.");
                 reth_fs_util::remove_file(path)?;
@@ -253,17 +392,70 @@ impl<H: NippyJarHeader> NippyJar<H> {
         DataReader::new(self.data_path())
     }
 
-    /// Writes all necessary configuration to file.
-    fn freeze_config(&self) -> Result<(), NippyJarError> {
-        Ok(reth_fs_util::atomic_write_file(&self.config_path(), |file| {
-            bincode::serialize_into(file, &self)
-        })?)
+    /// Returns a [`DataReader`] of the data and offset file, with all their pages faulted in.
+    ///
+    /// See [`DataReader::new_populated`].
+    pub fn open_populated_data_reader(&self) -> Result<DataReader, NippyJarError> {
+        DataReader::new_populated(self.data_path())
     }
-}
 
-#[cfg(test)]
-impl<H: NippyJarHeader> NippyJar<H> {
-    /// If required, prepares any compression algorithm to an early pass of the data.
+    /// Returns a [`DataReader`] of the data and offset file, with the given access pattern
+    /// [`MmapAdvice`] applied to them.
+    pub fn open_data_reader_with_advice(
+        &self,
+        advice: MmapAdvice,
+    ) -> Result<DataReader, NippyJarError> {
+        let reader = self.open_data_reader()?;
+        reader.advise(advice)?;
+        Ok(reader)
+    }
+
+    /// Writes all data and configuration to a file and the offset index to another, committing them
+    /// to disk every `checkpoint_interval` rows.
+    ///
+    /// If the jar already has a configuration on disk, it's loaded instead, and freezing resumes
+    /// after its last committed row. Rows committed before the interruption are skipped from
+    /// `columns`, which should yield the same values from the beginning.
+    pub fn freeze_resumable(
+        self,
+        columns: Vec<impl IntoIterator<Item = ColumnResult<Vec<u8>>>>,
+        total_rows: u64,
+        checkpoint_interval: NonZeroUsize,
+    ) -> Result<Self, NippyJarError> {
+        let jar = if self.config_path().exists() { Self::load(self.data_path())? } else { self };
+        jar.check_before_freeze(&columns)?;
+
+        let path = jar.data_path().to_path_buf();
+
+        // Heals any uncommitted data left behind by an interrupted freeze
+        let mut writer = NippyJarWriter::new(jar)?;
+        let committed = writer.rows();
+        if committed > 0 {
+            debug!(target: "nippy-jar", ?path, committed, "Resuming freeze.");
+        }
+
+        let mut columns = columns
+            .into_iter()
+            .map(|column| column.into_iter().skip(committed))
+            .collect::<Vec<_>>();
+
+        let mut remaining = total_rows.saturating_sub(committed as u64);
+        while remaining > 0 {
+            let rows = remaining.min(checkpoint_interval.get() as u64);
+            writer.append_rows(columns.iter_mut().collect(), rows)?;
+            writer.commit()?;
+            remaining -= rows;
+        }
+        ensure_exhausted(&mut columns, writer.rows() as u64)?;
+
+        debug!(target: "nippy-jar", ?writer, "Finished writing data.");
+
+        Ok(writer.into_jar())
+    }
+
+    /// If required, prepares any compression algorithm to an early pass of the data, e.g. trains
+    /// the zstd dictionary of each column. Needs to be called before writing data with a zstd
+    /// compressor using dictionaries.
     pub fn prepare_compression(
         &mut self,
         columns: Vec<impl IntoIterator<Item = Vec<u8>>>,
@@ -276,28 +468,72 @@ impl<H: NippyJarHeader> NippyJar<H> {
         Ok(())
     }
 
-    /// Writes all data and configuration to a file and the offset index to another.
-    pub fn freeze(
-        self,
+    /// Writes all data to `data` and the offset index to `offsets`, in the same layout as the data
+    /// and offsets files, without any file system interaction.
+    ///
+    /// Useful to freeze into memory or any other sink. The configuration isn't written, and can be
+    /// persisted with [`Self::save_to_writer`]. The written data can be read with
+    /// [`NippyJarCursor::from_parts`]. Fails with [`NippyJarError::FrozenJar`] if the jar already
+    /// has rows.
+    pub fn freeze_into(
+        mut self,
         columns: Vec<impl IntoIterator<Item = ColumnResult<Vec<u8>>>>,
         total_rows: u64,
+        mut data: impl Write,
+        mut offsets: impl Write,
     ) -> Result<Self, NippyJarError> {
+        if self.rows != 0 {
+            return Err(NippyJarError::FrozenJar)
+        }
         self.check_before_freeze(&columns)?;
 
-        debug!(target: "nippy-jar", path=?self.data_path(), "Opening data file.");
+        let mut columns = columns.into_iter().map(IntoIterator::into_iter).collect::<Vec<_>>();
+        let mut values = ValueWriter::new(0);
 
-        // Creates the writer, data and offsets file
-        let mut writer = NippyJarWriter::new(self)?;
+        offsets.write_all(&[OFFSET_SIZE_BYTES])?;
+        offsets.write_all(&values.data_size.to_le_bytes())?;
+        for _ in 0..total_rows {
+            for (column, column_iter) in columns.iter_mut().enumerate() {
+                let value = next_column_value(column_iter, self.rows as u64, column)?;
+                values.write_value(&self, &value, &mut data)?;
 
-        // Append rows to file while holding offsets in memory
-        writer.append_rows(columns, total_rows)?;
+                // Offset of the next value. The last one represents the size of the data
+                offsets.write_all(&values.data_size.to_le_bytes())?;
+            }
 
-        // Flushes configuration and offsets to disk
-        writer.commit()?;
+            let (row_size, _) = values.finish_row();
+            self.max_row_size = self.max_row_size.max(row_size);
+            self.rows += 1;
+        }
+        ensure_exhausted(&mut columns, self.rows as u64)?;
 
-        debug!(target: "nippy-jar", ?writer, "Finished writing data.");
+        data.flush()?;
+        offsets.flush()?;
 
-        Ok(writer.into_jar())
+        Ok(self)
+    }
+
+    /// Runs the same checks as a freeze of `columns` without writing anything: the number of
+    /// columns, the readiness of the compressor, and the availability of `total_rows` values in
+    /// every column.
+    ///
+    /// Drains the column iterators.
+    pub fn validate_freeze(
+        &self,
+        columns: Vec<impl IntoIterator<Item = ColumnResult<Vec<u8>>>>,
+        total_rows: u64,
+    ) -> Result<(), NippyJarError> {
+        self.check_before_freeze(&columns)?;
+
+        let mut columns = columns.into_iter().map(IntoIterator::into_iter).collect::<Vec<_>>();
+        for row in 0..total_rows {
+            for (column, column_iter) in columns.iter_mut().enumerate() {
+                next_column_value(column_iter, self.rows as u64 + row, column)?;
+            }
+        }
+        ensure_exhausted(&mut columns, self.rows as u64 + total_rows)?;
+
+        Ok(())
     }
 
     /// Safety checks before creating and returning a [`File`] handle to write data to.
@@ -317,6 +553,88 @@ impl<H: NippyJarHeader> NippyJar<H> {
 
         Ok(())
     }
+
+    /// Writes all necessary configuration to file.
+    fn freeze_config(&self) -> Result<(), NippyJarError> {
+        Ok(reth_fs_util::atomic_write_file(&self.config_path(), |file| {
+            bincode::serialize_into(file, &self)
+        })?)
+    }
+}
+
+#[cfg(test)]
+impl<H: NippyJarHeader> NippyJar<H> {
+    /// Writes all data and configuration to a file and the offset index to another.
+    pub fn freeze(
+        self,
+        columns: Vec<impl IntoIterator<Item = ColumnResult<Vec<u8>>>>,
+        total_rows: u64,
+    ) -> Result<Self, NippyJarError> {
+        self.check_before_freeze(&columns)?;
+
+        debug!(target: "nippy-jar", path=?self.data_path(), "Opening data file.");
+
+        // Creates the writer, data and offsets file
+        let mut writer = NippyJarWriter::new(self)?;
+
+        // Append rows to file while holding offsets in memory
+        let mut columns = columns.into_iter().map(IntoIterator::into_iter).collect::<Vec<_>>();
+        writer.append_rows(columns.iter_mut().collect(), total_rows)?;
+        ensure_exhausted(&mut columns, writer.rows() as u64)?;
+
+        // Flushes configuration and offsets to disk
+        writer.commit()?;
+
+        debug!(target: "nippy-jar", ?writer, "Finished writing data.");
+
+        Ok(writer.into_jar())
+    }
+}
+
+/// Returns the next value of the iterator of `column`, reporting the position of a missing or
+/// failed value.
+pub(crate) fn next_column_value<T>(
+    column_iter: &mut impl Iterator<Item = ColumnResult<T>>,
+    row: u64,
+    column: usize,
+) -> Result<T, NippyJarError> {
+    match column_iter.next() {
+        Some(Ok(value)) => Ok(value),
+        Some(Err(source)) => Err(NippyJarError::ColumnValue { row, column: column as u64, source }),
+        None => Err(NippyJarError::UnexpectedMissingValue(row, column as u64)),
+    }
+}
+
+/// Makes sure that no column iterator has a value left for `row`, which is past the last row.
+fn ensure_exhausted(
+    columns: &mut [impl Iterator<Item = ColumnResult<Vec<u8>>>],
+    row: u64,
+) -> Result<(), NippyJarError> {
+    for (column, column_iter) in columns.iter_mut().enumerate() {
+        if column_iter.next().is_some() {
+            return Err(NippyJarError::UnexpectedExtraValue(row, column as u64))
+        }
+    }
+    Ok(())
+}
+
+/// Reads a field appended to the original [`NippyJar`] layout, serialized as an `Option`.
+/// Configurations written before the field existed end right before it, so it's `None` if `reader`
+/// is exhausted. A truncated field is an error.
+fn read_appended_field<T: DeserializeOwned>(
+    mut reader: impl Read,
+) -> Result<Option<T>, NippyJarError> {
+    let mut tag = [0u8; 1];
+    loop {
+        match reader.read(&mut tag) {
+            Ok(0) => return Ok(None),
+            Ok(_) => break,
+            Err(err) if err.kind() == std::io::ErrorKind::Interrupted => {}
+            Err(err) => return Err(err.into()),
+        }
+    }
+
+    Ok(bincode::deserialize_from(tag.chain(reader))?)
 }
 
 /// Manages the reading of static file data using memory-mapped files.
@@ -325,7 +643,7 @@ impl<H: NippyJarHeader> NippyJar<H> {
 #[derive(Debug)]
 pub struct DataReader {
     /// Data file descriptor. Needs to be kept alive as long as `data_mmap` handle.
-    #[expect(dead_code)]
+    #[cfg_attr(not(unix), expect(dead_code))]
     data_file: File,
     /// Mmap handle for data.
     data_mmap: Mmap,
@@ -340,23 +658,28 @@ pub struct DataReader {
 impl DataReader {
     /// Reads the respective data and offsets file and returns [`DataReader`].
     pub fn new(path: impl AsRef<Path>) -> Result<Self, NippyJarError> {
-        let data_file = File::open(path.as_ref())?;
-        // SAFETY: File is read-only and its descriptor is kept alive as long as the mmap handle.
-        let data_mmap = unsafe { Mmap::map(&data_file)? };
+        Self::open(path.as_ref(), &MmapOptions::new())
+    }
+
+    /// Reads the respective data and offsets file and returns [`DataReader`], with all their
+    /// pages faulted in upfront (`MAP_POPULATE`).
+    ///
+    /// Opening takes longer, but the first accesses don't stall on page faults. Only has an effect
+    /// on Linux. For a range of rows, see [`NippyJarCursor::prefetch_rows`].
+    pub fn new_populated(path: impl AsRef<Path>) -> Result<Self, NippyJarError> {
+        Self::open(path.as_ref(), MmapOptions::new().populate())
+    }
 
-        let offset_file = File::open(path.as_ref().with_extension(OFFSETS_FILE_EXTENSION))?;
+    fn open(path: &Path, options: &MmapOptions) -> Result<Self, NippyJarError> {
+        let data_file = File::open(path)?;
         // SAFETY: File is read-only and its descriptor is kept alive as long as the mmap handle.
-        let offset_mmap = unsafe { Mmap::map(&offset_file)? };
+        let data_mmap = unsafe { options.map(&data_file)? };
 
-        // First byte is the size of one offset in bytes
-        let offset_size = offset_mmap[0];
+        let offset_file = File::open(path.with_extension(OFFSETS_FILE_EXTENSION))?;
+        // SAFETY: File is read-only and its descriptor is kept alive as long as the mmap handle.
+        let offset_mmap = unsafe { options.map(&offset_file)? };
 
-        // Ensure that the size of an offset is at most 8 bytes.
-        if offset_size > 8 {
-            return Err(NippyJarError::OffsetSizeTooBig { offset_size })
-        } else if offset_size == 0 {
-            return Err(NippyJarError::OffsetSizeTooSmall { offset_size })
-        }
+        let offset_size = read_offset_size(&offset_mmap)?;
 
         Ok(Self { data_file, data_mmap, offset_file, offset_size, offset_mmap })
     }
@@ -391,15 +714,7 @@ impl DataReader {
 
     /// Reads one offset-sized (determined by the offset file) u64 at the provided index.
     fn offset_at(&self, index: usize) -> Result<u64, NippyJarError> {
-        let mut buffer: [u8; 8] = [0; 8];
-
-        let offset_end = index.saturating_add(self.offset_size as usize);
-        if offset_end > self.offset_mmap.len() {
-            return Err(NippyJarError::OffsetOutOfBounds { index })
-        }
-
-        buffer[..self.offset_size as usize].copy_from_slice(&self.offset_mmap[index..offset_end]);
-        Ok(u64::from_le_bytes(buffer))
+        read_offset_at(&self.offset_mmap, self.offset_size, index)
     }
 
     /// Returns number of bytes that represent one offset.
@@ -416,6 +731,171 @@ impl DataReader {
     pub fn size(&self) -> usize {
         self.data_mmap.len()
     }
+
+    /// Takes a shared advisory lock on the data file, held until the reader is dropped.
+    ///
+    /// While held, [`NippyJarWriter::try_lock_exclusive`] on the same jar fails, so writers that
+    /// lock can't modify a file that a live reader has mapped. Fails with
+    /// [`NippyJarError::FileLocked`] if a writer holds the lock.
+    #[cfg(unix)]
+    pub fn try_lock_shared(&self) -> Result<(), NippyJarError> {
+        lock::try_lock(&self.data_file, false)
+    }
+
+    /// Advises the OS on how the data and offsets files will be accessed.
+    ///
+    /// This is a hint, and does nothing on non-unix platforms.
+    pub fn advise(&self, advice: MmapAdvice) -> Result<(), NippyJarError> {
+        advise_range(&self.data_mmap, advice, 0..self.data_mmap.len())?;
+        advise_range(&self.offset_mmap, advice, 0..self.offset_mmap.len())
+    }
+
+    /// Advises the OS that the data and offsets of the given offset indexes will be read soon,
+    /// so that their pages can be read ahead of time.
+    ///
+    /// This is a hint, and does nothing on non-unix platforms.
+    pub fn prefetch(&self, indexes: Range<usize>) -> Result<(), NippyJarError> {
+        if indexes.is_empty() {
+            return Ok(())
+        }
+
+        let data_start = self.offset(indexes.start)? as usize;
+        let data_end = match self.offset(indexes.end) {
+            Ok(offset) => offset as usize,
+            Err(NippyJarError::OffsetOutOfBounds { .. }) => self.size(),
+            Err(err) => return Err(err),
+        };
+        let offset_size = self.offset_size as usize;
+        let offsets_start = indexes.start * offset_size + 1;
+        let offsets_end = ((indexes.end + 1) * offset_size + 1).min(self.offset_mmap.len());
+
+        advise_range(&self.data_mmap, MmapAdvice::WillNeed, data_start..data_end)?;
+        advise_range(&self.offset_mmap, MmapAdvice::WillNeed, offsets_start..offsets_end)?;
+        Ok(())
+    }
+}
+
+/// Access pattern hint for the memory-mapped files of a [`DataReader`].
+///
+/// Applied with `madvise`, and ignored on non-unix platforms.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MmapAdvice {
+    /// No special treatment, which is the kernel default.
+    Normal,
+    /// Pages are accessed in random order, so read-ahead is disabled. Suited for point lookups.
+    Random,
+    /// Pages are accessed sequentially, so read-ahead is aggressive. Suited for full scans.
+    Sequential,
+    /// Pages will be accessed soon, so they are read ahead of time.
+    WillNeed,
+}
+
+/// Applies `advice` to the given range of `mmap`.
+#[cfg(unix)]
+fn advise_range(mmap: &Mmap, advice: MmapAdvice, range: Range<usize>) -> Result<(), NippyJarError> {
+    let advice = match advice {
+        MmapAdvice::Normal => memmap2::Advice::Normal,
+        MmapAdvice::Random => memmap2::Advice::Random,
+        MmapAdvice::Sequential => memmap2::Advice::Sequential,
+        MmapAdvice::WillNeed => memmap2::Advice::WillNeed,
+    };
+    if range.start < range.end && range.end <= mmap.len() {
+        mmap.advise_range(advice, range.start, range.len())?;
+    }
+    Ok(())
+}
+
+/// Applies `advice` to the given range of `mmap`.
+#[cfg(not(unix))]
+fn advise_range(
+    _mmap: &Mmap,
+    _advice: MmapAdvice,
+    _range: Range<usize>,
+) -> Result<(), NippyJarError> {
+    Ok(())
+}
+
+/// Reads the data and offsets of a static file from caller-provided byte slices.
+///
+/// Unlike [`DataReader`], it doesn't interact with the file system, which makes it suitable for
+/// data fetched from remote storage or embedded in a binary.
+#[derive(Debug, Clone, Copy)]
+pub struct SliceReader<'a> {
+    /// Content of the data file.
+    data: &'a [u8],
+    /// Content of the offsets file.
+    offsets: &'a [u8],
+    /// Number of bytes that represent one offset.
+    offset_size: u8,
+}
+
+impl<'a> SliceReader<'a> {
+    /// Creates a [`SliceReader`] from the content of the offsets and data files.
+    ///
+    /// Fails with [`NippyJarError::OffsetOutOfBounds`] if the last offset, which represents the
+    /// size of the data, points past the end of `data`.
+    pub fn new(offsets: &'a [u8], data: &'a [u8]) -> Result<Self, NippyJarError> {
+        let offset_size = read_offset_size(offsets)?;
+
+        let num_offsets = (offsets.len() - 1) / offset_size as usize;
+        if num_offsets > 0 {
+            let index = (num_offsets - 1) * offset_size as usize + 1;
+            if read_offset_at(offsets, offset_size, index)? > data.len() as u64 {
+                return Err(NippyJarError::OffsetOutOfBounds { index })
+            }
+        }
+
+        Ok(Self { data, offsets, offset_size })
+    }
+
+    /// Returns the offset for the requested data index
+    pub fn offset(&self, index: usize) -> Result<u64, NippyJarError> {
+        // + 1 represents the offset_len u8 which is in the beginning of the file
+        read_offset_at(self.offsets, self.offset_size, index * self.offset_size as usize + 1)
+    }
+
+    /// Returns number of bytes that represent one offset.
+    pub const fn offset_size(&self) -> u8 {
+        self.offset_size
+    }
+
+    /// Returns the underlying data as a slice of bytes for the provided range.
+    pub fn data(&self, range: Range<usize>) -> &'a [u8] {
+        &self.data[range]
+    }
+
+    /// Returns total size of data
+    pub const fn size(&self) -> usize {
+        self.data.len()
+    }
+}
+
+/// Reads and validates the size of one offset, stored as the first byte of an offsets file.
+fn read_offset_size(offsets: &[u8]) -> Result<u8, NippyJarError> {
+    // First byte is the size of one offset in bytes
+    let offset_size = *offsets.first().ok_or(NippyJarError::OffsetOutOfBounds { index: 0 })?;
+
+    // Ensure that the size of an offset is at most 8 bytes.
+    if offset_size > 8 {
+        return Err(NippyJarError::OffsetSizeTooBig { offset_size })
+    } else if offset_size == 0 {
+        return Err(NippyJarError::OffsetSizeTooSmall { offset_size })
+    }
+
+    Ok(offset_size)
+}
+
+/// Reads one `offset_size` u64 at the provided byte index of an offsets file.
+fn read_offset_at(offsets: &[u8], offset_size: u8, index: usize) -> Result<u64, NippyJarError> {
+    let mut buffer: [u8; 8] = [0; 8];
+
+    let offset_end = index.saturating_add(offset_size as usize);
+    if offset_end > offsets.len() {
+        return Err(NippyJarError::OffsetOutOfBounds { index })
+    }
+
+    buffer[..offset_size as usize].copy_from_slice(&offsets[index..offset_end]);
+    Ok(u64::from_le_bytes(buffer))
 }
 
 #[cfg(test)]
@@ -423,7 +903,11 @@ mod tests {
     use super::*;
     use compression::Compression;
     use rand::{rngs::SmallRng, seq::SliceRandom, RngCore, SeedableRng};
-    use std::{fs::OpenOptions, io::Read};
+    use std::{
+        fs::OpenOptions,
+        io::{Read, Seek, SeekFrom},
+        sync::Arc,
+    };
 
     type ColumnResults<T> = Vec<ColumnResult<T>>;
     type ColumnValues = Vec<Vec<u8>>;
@@ -447,7 +931,7 @@ mod tests {
         (entry_gen(), entry_gen())
     }
 
-    fn clone_with_result(col: &ColumnValues) -> ColumnResults<Vec<u8>> {
+    fn clone_with_result(col: &[Vec<u8>]) -> ColumnResults<Vec<u8>> {
         col.iter().map(|v| Ok(v.clone())).collect()
     }
 
@@ -1071,4 +1555,1639 @@ mod tests {
         // runs the consistency check.
         let _ = NippyJarWriter::new(nippy).unwrap();
     }
+
+    /// Freezes `col1` and `col2` into a two column jar compressed with [`compression::Lz4`].
+    fn freeze_lz4_jar(file_path: &Path, col1: &[Vec<u8>], col2: &[Vec<u8>]) -> NippyJar {
+        NippyJar::new_without_header(2, file_path)
+            .with_lz4()
+            .freeze(vec![clone_with_result(col1), clone_with_result(col2)], col1.len() as u64)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_cursor_deadline() {
+        let (col1, col2) = test_data(None);
+        let file_path = tempfile::NamedTempFile::new().unwrap();
+        let nippy = freeze_lz4_jar(file_path.path(), &col1, &col2);
+
+        let mut cursor = NippyJarCursor::new(&nippy)
+            .unwrap()
+            .with_deadline(std::time::Instant::now() + std::time::Duration::from_secs(3600));
+        assert_eq!(cursor.next_row().unwrap().unwrap(), vec![&col1[0][..], &col2[0][..]]);
+
+        cursor.set_deadline(Some(std::time::Instant::now()));
+        assert!(matches!(cursor.next_row(), Err(NippyJarError::DeadlineExceeded)));
+        assert!(matches!(
+            cursor.row_by_number_with_cols(0, 0b01),
+            Err(NippyJarError::DeadlineExceeded)
+        ));
+
+        cursor.set_deadline(None);
+        assert_eq!(cursor.row_by_number(1).unwrap().unwrap(), vec![&col1[1][..], &col2[1][..]]);
+    }
+
+    #[test]
+    fn test_cursor_from_parts() {
+        let (col1, col2) = test_data(None);
+        let file_path = tempfile::NamedTempFile::new().unwrap();
+        let nippy = freeze_lz4_jar(file_path.path(), &col1, &col2);
+
+        let config = reth_fs_util::read(nippy.config_path()).unwrap();
+        let offsets = reth_fs_util::read(nippy.offsets_path()).unwrap();
+        let data = reth_fs_util::read(nippy.data_path()).unwrap();
+
+        let loaded_nippy = NippyJar::<()>::load_from_reader(&config[..]).unwrap();
+        let mut cursor = NippyJarCursor::from_parts(&loaded_nippy, &offsets, &data).unwrap();
+
+        let mut row_index = 0usize;
+        while let Some(row) = cursor.next_row().unwrap() {
+            assert_eq!(row, vec![&col1[row_index][..], &col2[row_index][..]]);
+            row_index += 1;
+        }
+        assert_eq!(row_index, col1.len());
+
+        assert!(matches!(
+            NippyJarCursor::from_parts(&loaded_nippy, &[], &data),
+            Err(NippyJarError::OffsetOutOfBounds { index: 0 })
+        ));
+    }
+
+    #[test]
+    fn test_writer_append_rows_with_commits() {
+        let (col1, col2) = test_data(None);
+        let file_path = tempfile::NamedTempFile::new().unwrap();
+        let nippy = NippyJar::new_without_header(2, file_path.path()).with_lz4();
+        let mut writer = NippyJarWriter::new(nippy).unwrap();
+
+        let (tx, rx) = std::sync::mpsc::sync_channel(4);
+        let rows = col1.iter().cloned().zip(col2.iter().cloned()).collect::<Vec<_>>();
+        let producer = std::thread::spawn(move || {
+            for (v0, v1) in rows {
+                tx.send(vec![v0, v1]).unwrap();
+            }
+        });
+
+        let path = file_path.path().to_path_buf();
+        let rx = rx.into_iter().enumerate().map(|(row_num, row)| {
+            // Every commit leaves a loadable jar with all rows up to the last checkpoint.
+            let committed = NippyJar::load_without_header(&path).unwrap();
+            assert_eq!(committed.rows(), row_num / 10 * 10);
+            row
+        });
+        let appended = writer.append_rows_with_commits(rx, NonZeroUsize::new(10).unwrap()).unwrap();
+        producer.join().unwrap();
+
+        assert_eq!(appended, col1.len());
+        assert!(!writer.is_dirty());
+
+        let loaded_nippy = NippyJar::load_without_header(file_path.path()).unwrap();
+        assert_eq!(loaded_nippy.rows(), col1.len());
+        let mut cursor = NippyJarCursor::new(&loaded_nippy).unwrap();
+        for (v0, v1) in col1.iter().zip(&col2) {
+            assert_eq!(cursor.next_row().unwrap().unwrap(), vec![&v0[..], &v1[..]]);
+        }
+
+        // Rows with a missing or an extra column value are rejected without writing anything.
+        let mut writer = NippyJarWriter::new(loaded_nippy).unwrap();
+        let data_size = std::fs::metadata(file_path.path()).unwrap().len();
+        assert!(matches!(
+            writer.append_rows_with_commits([vec![&col1[0]]], NonZeroUsize::MIN),
+            Err(NippyJarError::ColumnLenMismatch(2, 1))
+        ));
+        assert!(matches!(
+            writer
+                .append_rows_with_commits([vec![&col1[0], &col2[0], &col2[0]]], NonZeroUsize::MIN),
+            Err(NippyJarError::ColumnLenMismatch(2, 3))
+        ));
+        assert_eq!(writer.rows(), col1.len());
+        assert_eq!(writer.column(), 0);
+        assert!(writer.offsets().is_empty());
+
+        writer.commit().unwrap();
+        assert_eq!(std::fs::metadata(file_path.path()).unwrap().len(), data_size);
+        assert_eq!(NippyJar::load_without_header(file_path.path()).unwrap().rows(), col1.len());
+    }
+
+    #[test]
+    fn test_zstd_column_dictionaries() {
+        let (col1, col2) = test_data(None);
+        let num_rows = col1.len() as u64;
+        let file_path = tempfile::NamedTempFile::new().unwrap();
+
+        // Only the first column is trained with a dictionary.
+        let mut nippy = NippyJar::new_without_header(2, file_path.path())
+            .with_zstd_column_dicts(vec![Some(5000), None]);
+        nippy.prepare_compression(vec![col1.clone(), col2.clone()]).unwrap();
+
+        if let Some(Compressors::Zstd(zstd)) = nippy.compressor() {
+            assert_eq!(zstd.max_dict_size, 5000);
+            assert!(!zstd.is_column_dictionary_disabled(0));
+            assert!(zstd.is_column_dictionary_disabled(1));
+            assert_eq!(zstd.compressors().unwrap().unwrap().len(), 2);
+        }
+
+        nippy.freeze(vec![clone_with_result(&col1), clone_with_result(&col2)], num_rows).unwrap();
+
+        let loaded_nippy = NippyJar::load_without_header(file_path.path()).unwrap();
+        let Some(Compressors::Zstd(zstd)) = loaded_nippy.compressor() else {
+            panic!("Expected Zstd compressor")
+        };
+        assert!(zstd.use_dict);
+        assert!(!zstd.is_column_dictionary_disabled(0));
+        assert!(zstd.is_column_dictionary_disabled(1));
+        assert_eq!(zstd.decompressors().unwrap().len(), 2);
+
+        let mut cursor = NippyJarCursor::new(&loaded_nippy).unwrap();
+        for (v0, v1) in col1.iter().zip(&col2) {
+            assert_eq!(cursor.next_row().unwrap().unwrap(), vec![&v0[..], &v1[..]]);
+        }
+
+        // Values of the first column can only be decompressed with its dictionary.
+        let stored = cursor.raw_value_by_row_col(0, 0).unwrap().unwrap().data.to_vec();
+        assert!(zstd::decode_all(&stored[..]).is_err());
+        let stored = cursor.raw_value_by_row_col(0, 1).unwrap().unwrap().data.to_vec();
+        assert_eq!(zstd::decode_all(&stored[..]).unwrap(), col2[0]);
+        drop(cursor);
+
+        // Appending to a loaded jar compresses with, and persists, the same dictionaries.
+        let mut writer = NippyJarWriter::new(loaded_nippy).unwrap();
+        writer.append_row(&[&col1[0], &col2[0]]).unwrap();
+        writer.commit().unwrap();
+        drop(writer);
+
+        let loaded_nippy = NippyJar::load_without_header(file_path.path()).unwrap();
+        assert_eq!(loaded_nippy.rows, col1.len() + 1);
+        let mut cursor = NippyJarCursor::new(&loaded_nippy).unwrap();
+        assert_eq!(
+            cursor.row_by_number(col1.len()).unwrap().unwrap(),
+            vec![&col1[0][..], &col2[0][..]]
+        );
+    }
+
+    #[test]
+    fn test_raw_columns() {
+        let (col1, col2) = test_data(None);
+        let num_rows = col1.len() as u64;
+        let file_path = tempfile::NamedTempFile::new().unwrap();
+
+        // The second column is stored without compression.
+        let nippy =
+            NippyJar::new_without_header(2, file_path.path()).with_lz4().with_raw_columns(0b10);
+        assert!(nippy.column_compressor(0).is_some());
+        assert!(nippy.column_compressor(1).is_none());
+        let nippy = nippy
+            .freeze(vec![clone_with_result(&col1), clone_with_result(&col2)], num_rows)
+            .unwrap();
+
+        // Configurations without raw columns keep the original layout.
+        let legacy = NippyJar::new_without_header(2, file_path.path()).with_lz4();
+        let legacy_config = bincode::serialize(&legacy).unwrap();
+        assert_eq!(bincode::serialized_size(&nippy).unwrap(), legacy_config.len() as u64 + 9);
+        assert_eq!(NippyJar::<()>::load_from_reader(&legacy_config[..]).unwrap().raw_columns, None);
+
+        // Configurations which are corrupt past the original layout aren't mistaken for legacy
+        // ones.
+        let config = bincode::serialize(&nippy).unwrap();
+        let invalid_tag = [&legacy_config[..], &[2]].concat();
+        assert!(matches!(
+            NippyJar::<()>::load_from_reader(&invalid_tag[..]),
+            Err(NippyJarError::Bincode(err)) if matches!(*err, bincode::ErrorKind::InvalidTagEncoding(2))
+        ));
+        for truncated in [legacy_config.len() + 1, legacy_config.len() + 5] {
+            assert!(matches!(
+                NippyJar::<()>::load_from_reader(&config[..truncated]),
+                Err(NippyJarError::Bincode(err)) if matches!(&*err, bincode::ErrorKind::Io(err) if err.kind() == std::io::ErrorKind::UnexpectedEof)
+            ));
+        }
+
+        let loaded_nippy = NippyJar::load_without_header(file_path.path()).unwrap();
+        assert_eq!(loaded_nippy, nippy);
+        assert!(loaded_nippy.is_raw_column(1));
+
+        let reader = loaded_nippy.open_data_reader().unwrap();
+        let mut cursor = NippyJarCursor::new(&loaded_nippy).unwrap();
+        for (row_num, (v0, v1)) in col1.iter().zip(&col2).enumerate() {
+            assert_eq!(cursor.next_row().unwrap().unwrap(), vec![&v0[..], &v1[..]]);
+
+            // Raw values are stored as they are.
+            let offset = reader.offset(row_num * 2 + 1).unwrap() as usize;
+            assert_eq!(reader.data(offset..offset + v1.len()), &v1[..]);
+        }
+    }
+
+    #[test]
+    fn test_config_hash() {
+        let (col1, col2) = test_data(None);
+        let file_path = tempfile::NamedTempFile::new().unwrap();
+        let other_path = tempfile::NamedTempFile::new().unwrap();
+
+        let nippy = freeze_lz4_jar(file_path.path(), &col1, &col2);
+        let hash = nippy.config_hash().unwrap();
+
+        // Data dependent state doesn't change the digest.
+        let empty = NippyJar::new(2, other_path.path(), 1u64).with_lz4();
+        assert_eq!(empty.config_hash().unwrap(), hash);
+        let loaded_nippy = NippyJar::load_without_header(file_path.path()).unwrap();
+        assert_eq!(loaded_nippy.config_hash().unwrap(), hash);
+
+        // Configuration does.
+        for other in [
+            NippyJar::new_without_header(3, other_path.path()).with_lz4(),
+            NippyJar::new_without_header(2, other_path.path()),
+            NippyJar::new_without_header(2, other_path.path()).with_zstd(false, 0),
+            NippyJar::new_without_header(2, other_path.path()).with_lz4().with_raw_columns(0b01),
+        ] {
+            assert_ne!(other.config_hash().unwrap(), hash);
+        }
+
+        // Including the columns which opted out of dictionaries, before and after a round-trip.
+        let zstd = NippyJar::new_without_header(2, other_path.path()).with_zstd(true, 5000);
+        let mut column_dicts = NippyJar::new_without_header(2, file_path.path())
+            .with_zstd_column_dicts(vec![Some(5000), None]);
+        let hash = column_dicts.config_hash().unwrap();
+        assert_ne!(zstd.config_hash().unwrap(), hash);
+
+        column_dicts.prepare_compression(vec![col1.clone(), col2.clone()]).unwrap();
+        assert_eq!(column_dicts.config_hash().unwrap(), hash);
+        column_dicts
+            .freeze(vec![clone_with_result(&col1), clone_with_result(&col2)], col1.len() as u64)
+            .unwrap();
+        let loaded_nippy = NippyJar::load_without_header(file_path.path()).unwrap();
+        assert_eq!(loaded_nippy.config_hash().unwrap(), hash);
+    }
+
+    #[test]
+    fn test_cursor_rows_iter() {
+        let (col1, col2) = test_data(None);
+        let file_path = tempfile::NamedTempFile::new().unwrap();
+        let nippy = freeze_lz4_jar(file_path.path(), &col1, &col2);
+        let expected =
+            col1.iter().zip(&col2).map(|(v0, v1)| vec![v0.clone(), v1.clone()]).collect::<Vec<_>>();
+
+        let mut cursor = NippyJarCursor::new(&nippy).unwrap();
+        let rows = cursor.rows().collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(rows, expected);
+        assert_eq!(cursor.row_index(), col1.len() as u64);
+        assert_eq!(cursor.rows().count(), 0);
+
+        // Iterates from the current position of the cursor.
+        cursor.row_by_number(90).unwrap();
+        assert_eq!(cursor.rows().len(), 9);
+        assert_eq!(cursor.rows().map(Result::unwrap).collect::<Vec<_>>(), expected[91..]);
+
+        // Stops after an error.
+        cursor.reset();
+        cursor.set_deadline(Some(std::time::Instant::now()));
+        let mut rows = cursor.rows();
+        assert!(matches!(rows.next(), Some(Err(NippyJarError::DeadlineExceeded))));
+        assert!(rows.next().is_none());
+    }
+
+    #[test]
+    fn test_cursor_column_iter() {
+        let (col1, col2) = test_data(None);
+        let file_path = tempfile::NamedTempFile::new().unwrap();
+        let nippy = freeze_lz4_jar(file_path.path(), &col1, &col2);
+
+        let mut cursor = NippyJarCursor::new(&nippy).unwrap();
+        assert_eq!(cursor.column_iter(0).unwrap().collect::<Result<Vec<_>, _>>().unwrap(), col1);
+        assert_eq!(cursor.row_index(), col1.len() as u64);
+
+        let mut values =
+            cursor.column_iter(1).unwrap().rev().collect::<Result<Vec<_>, _>>().unwrap();
+        values.reverse();
+        assert_eq!(values, col2);
+
+        assert!(matches!(
+            cursor.column_iter(2),
+            Err(NippyJarError::ColumnOutOfBounds { column: 2, columns: 2 })
+        ));
+    }
+
+    #[test]
+    fn test_cursor_seek_to_row() {
+        let (col1, col2) = test_data(None);
+        let file_path = tempfile::NamedTempFile::new().unwrap();
+        let nippy = freeze_lz4_jar(file_path.path(), &col1, &col2);
+
+        let mut cursor = NippyJarCursor::new(&nippy).unwrap();
+        cursor.seek_to_row(40);
+        assert_eq!(cursor.row_index(), 40);
+        for row_num in 40..col1.len() {
+            let row = cursor.next_row().unwrap().unwrap();
+            assert_eq!(row, vec![&col1[row_num][..], &col2[row_num][..]]);
+        }
+        assert!(cursor.next_row().unwrap().is_none());
+
+        cursor.seek_to_row(col1.len() + 1);
+        assert!(cursor.next_row().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_shared_jar_concurrent_cursors() {
+        let (col1, col2) = test_data(None);
+        let file_path = tempfile::NamedTempFile::new().unwrap();
+        freeze_lz4_jar(file_path.path(), &col1, &col2);
+
+        let shared = SharedNippyJar::<()>::load(file_path.path()).unwrap();
+        std::thread::scope(|scope| {
+            for thread in 0..4 {
+                let shared = shared.clone();
+                let (col1, col2) = (&col1, &col2);
+                scope.spawn(move || {
+                    for row_num in (thread..col1.len()).step_by(4) {
+                        let mut cursor = shared.cursor();
+                        let row = cursor.row_by_number(row_num).unwrap().unwrap();
+                        assert_eq!(row, vec![&col1[row_num][..], &col2[row_num][..]]);
+                    }
+                });
+            }
+        });
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_shared_jar_par_rows() {
+        use rayon::prelude::*;
+
+        let (col1, col2) = test_data(None);
+        let file_path = tempfile::NamedTempFile::new().unwrap();
+        freeze_lz4_jar(file_path.path(), &col1, &col2);
+        let expected =
+            col1.iter().zip(&col2).map(|(v0, v1)| vec![v0.clone(), v1.clone()]).collect::<Vec<_>>();
+
+        let shared = SharedNippyJar::<()>::load(file_path.path()).unwrap();
+        let rows = shared.par_rows().collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(rows, expected);
+
+        let total_size = shared
+            .par_rows()
+            .map(|row| row.unwrap().iter().map(Vec::len).sum::<usize>())
+            .sum::<usize>();
+        assert_eq!(total_size, expected.iter().flatten().map(Vec::len).sum::<usize>());
+    }
+
+    #[test]
+    fn test_typed_cursor() {
+        #[derive(Debug, PartialEq, Eq)]
+        struct TestRow {
+            first: [u8; 32],
+            second: [u8; 32],
+        }
+
+        impl RowDecode for TestRow {
+            fn decode_row(row: &[&[u8]]) -> ColumnResult<Self> {
+                Ok(Self { first: row[0].try_into()?, second: row[1].try_into()? })
+            }
+        }
+
+        #[derive(Debug)]
+        struct ShortRow;
+
+        impl RowDecode for ShortRow {
+            fn decode_row(row: &[&[u8]]) -> ColumnResult<Self> {
+                let _: [u8; 16] = row[0].try_into()?;
+                Ok(Self)
+            }
+        }
+
+        let (col1, col2) = test_data(None);
+        let file_path = tempfile::NamedTempFile::new().unwrap();
+        let nippy = freeze_lz4_jar(file_path.path(), &col1, &col2);
+
+        let mut cursor = TypedCursor::<TestRow>::new(NippyJarCursor::new(&nippy).unwrap());
+        for (first, second) in col1.iter().zip(&col2) {
+            let row = cursor.next_row().unwrap().unwrap();
+            assert_eq!(
+                row,
+                TestRow {
+                    first: first[..].try_into().unwrap(),
+                    second: second[..].try_into().unwrap()
+                }
+            );
+        }
+        assert!(cursor.next_row().unwrap().is_none());
+        assert_eq!(cursor.row_by_number(3).unwrap().unwrap().first[..], col1[3]);
+
+        let mut cursor = TypedCursor::<ShortRow>::new(cursor.into_inner());
+        assert!(matches!(cursor.row_by_number(0), Err(NippyJarError::Internal(_))));
+    }
+
+    #[test]
+    fn test_cursor_prefetch_rows() {
+        let (col1, col2) = test_data(None);
+        let file_path = tempfile::NamedTempFile::new().unwrap();
+        let nippy = freeze_lz4_jar(file_path.path(), &col1, &col2);
+
+        let mut cursor = NippyJarCursor::new(&nippy).unwrap();
+        cursor.prefetch_rows(10..20).unwrap();
+        cursor.prefetch_rows(90..col1.len() + 10).unwrap();
+        cursor.prefetch_rows(col1.len()..col1.len() + 10).unwrap();
+        assert_eq!(cursor.row_index(), 0);
+        assert_eq!(cursor.row_by_number(10).unwrap().unwrap(), vec![&col1[10][..], &col2[10][..]]);
+    }
+
+    #[test]
+    fn test_cursor_row_by_number_into() {
+        let (col1, col2) = test_data(None);
+        let file_path = tempfile::NamedTempFile::new().unwrap();
+        let nippy = freeze_lz4_jar(file_path.path(), &col1, &col2);
+
+        let mut cursor = NippyJarCursor::new(&nippy).unwrap();
+        let mut values = vec![vec![0xff; 64]; 3];
+        for row_num in [5, 0, 99, 5] {
+            assert!(cursor.row_by_number_into(row_num, &mut values).unwrap());
+            assert_eq!(values, vec![col1[row_num].clone(), col2[row_num].clone()]);
+            assert_eq!(cursor.row_index(), row_num as u64 + 1);
+        }
+
+        assert!(!cursor.row_by_number_into(col1.len(), &mut values).unwrap());
+        assert_eq!(values, vec![col1[5].clone(), col2[5].clone()]);
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn test_async_cursor() {
+        let (col1, col2) = test_data(None);
+        let file_path = tempfile::NamedTempFile::new().unwrap();
+        freeze_lz4_jar(file_path.path(), &col1, &col2);
+
+        let shared = SharedNippyJar::<()>::load(file_path.path()).unwrap();
+        let mut cursor = AsyncNippyJarCursor::new(shared);
+        for (v0, v1) in col1.iter().zip(&col2) {
+            assert_eq!(cursor.next_row().await.unwrap().unwrap(), vec![v0.clone(), v1.clone()]);
+        }
+        assert!(cursor.next_row().await.unwrap().is_none());
+
+        assert_eq!(
+            cursor.row_by_number(7).await.unwrap().unwrap(),
+            vec![col1[7].clone(), col2[7].clone()]
+        );
+        assert_eq!(cursor.row_index(), 8);
+        assert_eq!(
+            cursor.row_by_number_with_cols(3, 0b10).await.unwrap().unwrap(),
+            vec![col2[3].clone()]
+        );
+        assert_eq!(
+            cursor.rows_by_numbers(vec![9, 1]).await.unwrap(),
+            vec![
+                Some(vec![col1[9].clone(), col2[9].clone()]),
+                Some(vec![col1[1].clone(), col2[1].clone()])
+            ]
+        );
+    }
+
+    #[test]
+    fn test_cursor_value_by_row_col() {
+        let (col1, col2) = test_data(None);
+        let file_path = tempfile::NamedTempFile::new().unwrap();
+        let nippy = freeze_lz4_jar(file_path.path(), &col1, &col2);
+
+        let mut cursor = NippyJarCursor::new(&nippy).unwrap();
+        for row_num in [0, 42, col1.len() - 1] {
+            assert_eq!(cursor.value_by_row_col(row_num, 0).unwrap().unwrap(), col1[row_num]);
+            assert_eq!(cursor.value_by_row_col(row_num, 1).unwrap().unwrap(), col2[row_num]);
+        }
+        assert!(cursor.value_by_row_col(col1.len(), 0).unwrap().is_none());
+        assert!(matches!(
+            cursor.value_by_row_col(0, 2),
+            Err(NippyJarError::ColumnOutOfBounds { column: 2, columns: 2 })
+        ));
+    }
+
+    #[test]
+    fn test_cursor_value_reader() {
+        let (col1, col2) = test_data(None);
+        let num_rows = col1.len() as u64;
+        let zstd_path = tempfile::NamedTempFile::new().unwrap();
+        let lz4_path = tempfile::NamedTempFile::new().unwrap();
+
+        // Zstd with and without a dictionary.
+        let mut zstd_nippy = NippyJar::new_without_header(2, zstd_path.path())
+            .with_zstd_column_dicts(vec![Some(5000), None]);
+        zstd_nippy.prepare_compression(vec![col1.clone(), col2.clone()]).unwrap();
+        zstd_nippy
+            .freeze(vec![clone_with_result(&col1), clone_with_result(&col2)], num_rows)
+            .unwrap();
+        let zstd_nippy = NippyJar::load_without_header(zstd_path.path()).unwrap();
+
+        // LZ4 and uncompressed.
+        let lz4_nippy = NippyJar::new_without_header(2, lz4_path.path())
+            .with_lz4()
+            .with_raw_columns(0b10)
+            .freeze(vec![clone_with_result(&col1), clone_with_result(&col2)], num_rows)
+            .unwrap();
+
+        for nippy in [&zstd_nippy, &lz4_nippy] {
+            let mut cursor = NippyJarCursor::new(nippy).unwrap();
+            for row_num in [0, 50, col1.len() - 1] {
+                for (column, expected) in [&col1, &col2].into_iter().enumerate() {
+                    let mut reader = cursor.value_reader(row_num, column).unwrap().unwrap();
+                    let mut value = Vec::new();
+                    let mut chunk = [0; 5];
+                    loop {
+                        let read = reader.read(&mut chunk).unwrap();
+                        if read == 0 {
+                            break
+                        }
+                        value.extend_from_slice(&chunk[..read]);
+                    }
+                    assert_eq!(value, expected[row_num]);
+                }
+            }
+            assert!(cursor.value_reader(col1.len(), 0).unwrap().is_none());
+        }
+    }
+
+    #[test]
+    fn test_cursor_position() {
+        let (col1, col2) = test_data(None);
+        let file_path = tempfile::NamedTempFile::new().unwrap();
+        let nippy = freeze_lz4_jar(file_path.path(), &col1, &col2);
+
+        let mut cursor = NippyJarCursor::new(&nippy).unwrap();
+        cursor.rows_in_range(0..30).for_each(|row| drop(row.unwrap()));
+        let checkpoint = bincode::serialize(&cursor.position()).unwrap();
+
+        // Resume from the checkpoint with a new cursor.
+        let mut cursor = NippyJarCursor::new(&nippy).unwrap();
+        assert_eq!(cursor.position(), CursorPosition::default());
+        cursor.set_position(bincode::deserialize(&checkpoint).unwrap());
+        assert_eq!(cursor.row_index(), 30);
+        assert_eq!(cursor.next_row().unwrap().unwrap(), vec![&col1[30][..], &col2[30][..]]);
+    }
+
+    #[test]
+    fn test_mmap_advice() {
+        let (col1, col2) = test_data(None);
+        let file_path = tempfile::NamedTempFile::new().unwrap();
+        let nippy = freeze_lz4_jar(file_path.path(), &col1, &col2);
+
+        for advice in
+            [MmapAdvice::Random, MmapAdvice::Sequential, MmapAdvice::WillNeed, MmapAdvice::Normal]
+        {
+            let reader = Arc::new(nippy.open_data_reader_with_advice(advice).unwrap());
+            let mut cursor = NippyJarCursor::with_reader(&nippy, reader).unwrap();
+            assert_eq!(cursor.row_by_number(7).unwrap().unwrap(), vec![&col1[7][..], &col2[7][..]]);
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_file_reader() {
+        let (col1, col2) = test_data(None);
+        let num_rows = col1.len() as u64;
+        let file_path = tempfile::NamedTempFile::new().unwrap();
+
+        // The second column is uncompressed, so it's copied from the read buffer.
+        let nippy = NippyJar::new_without_header(2, file_path.path())
+            .with_lz4()
+            .with_raw_columns(0b10)
+            .freeze(vec![clone_with_result(&col1), clone_with_result(&col2)], num_rows)
+            .unwrap();
+
+        let mut readers = vec![FileReader::new(nippy.data_path()).unwrap()];
+        #[cfg(target_os = "linux")]
+        match FileReader::new_direct(nippy.data_path()) {
+            Ok(reader) => readers.push(reader),
+            // Direct I/O is not supported by every file system.
+            Err(NippyJarError::Disconnect(err)) if err.raw_os_error() == Some(libc::EINVAL) => {}
+            Err(err) => panic!("{err}"),
+        }
+
+        let num_offsets = col1.len() * 2 + 1;
+        for reader in readers {
+            // Both offsets delimiting a value are read at once.
+            for index in [0, 41, num_offsets - 2] {
+                let expected = (reader.offset(index).unwrap(), reader.offset(index + 1).unwrap());
+                assert_eq!(reader.offsets(index).unwrap(), expected);
+            }
+            assert!(matches!(
+                reader.offsets(num_offsets - 1),
+                Err(NippyJarError::OffsetOutOfBounds { index }) if index == num_offsets * 8 + 1
+            ));
+
+            let mut cursor = NippyJarCursor::with_file_reader(&nippy, Arc::new(reader)).unwrap();
+            for (v0, v1) in col1.iter().zip(&col2) {
+                assert_eq!(cursor.next_row().unwrap().unwrap(), vec![&v0[..], &v1[..]]);
+            }
+            assert!(cursor.next_row().unwrap().is_none());
+            assert_eq!(cursor.value_by_row_col(42, 1).unwrap().unwrap(), col2[42]);
+        }
+    }
+
+    #[test]
+    fn test_cursor_row_cache() {
+        let (col1, col2) = test_data(None);
+        let file_path = tempfile::NamedTempFile::new().unwrap();
+        let nippy = freeze_lz4_jar(file_path.path(), &col1, &col2);
+
+        let cache = RowCache::new(2);
+        let mut cursor = NippyJarCursor::new(&nippy).unwrap().with_row_cache(cache.clone());
+
+        // Masked reads are not cached.
+        assert_eq!(cursor.row_by_number_with_cols(1, 0b10).unwrap().unwrap(), vec![&col2[1][..]]);
+        assert!(cache.is_empty());
+
+        for row_num in [1, 2, 3, 3] {
+            let row = cursor.row_by_number(row_num).unwrap().unwrap();
+            assert_eq!(row, vec![&col1[row_num][..], &col2[row_num][..]]);
+        }
+        assert_eq!(cache.len(), 2);
+
+        // Cached rows are served to other cursors sharing the cache, also for masked reads.
+        let mut other = NippyJarCursor::new(&nippy).unwrap().with_row_cache(cache.clone());
+        assert_eq!(other.row_by_number_with_cols(3, 0b10).unwrap().unwrap(), vec![&col2[3][..]]);
+        assert_eq!(other.row_by_number(2).unwrap().unwrap(), vec![&col1[2][..], &col2[2][..]]);
+
+        cache.clear();
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn test_cursor_row_by_sorted_key() {
+        let (mut col1, col2) = test_data(None);
+        col1.sort_unstable();
+        col1.dedup();
+        let col2 = &col2[..col1.len()];
+        let file_path = tempfile::NamedTempFile::new().unwrap();
+        let nippy = freeze_lz4_jar(file_path.path(), &col1, col2);
+
+        let mut cursor = NippyJarCursor::new(&nippy).unwrap();
+        for (row_num, key) in col1.iter().enumerate() {
+            assert_eq!(cursor.row_number_by_sorted_key(0, key).unwrap(), Some(row_num));
+            assert_eq!(
+                cursor.row_by_sorted_key(0, key).unwrap().unwrap(),
+                vec![&key[..], &col2[row_num][..]]
+            );
+        }
+
+        for missing in [vec![], vec![0xff; 33], vec![col1[0][0]]] {
+            assert!(cursor.row_by_sorted_key(0, &missing).unwrap().is_none());
+        }
+        assert!(matches!(
+            cursor.row_by_sorted_key(2, &col1[0]),
+            Err(NippyJarError::ColumnOutOfBounds { column: 2, columns: 2 })
+        ));
+
+        // The key column is checked regardless of the number of rows.
+        let empty_path = tempfile::NamedTempFile::new().unwrap();
+        let empty = freeze_lz4_jar(empty_path.path(), &[], &[]);
+        let mut cursor = NippyJarCursor::new(&empty).unwrap();
+        assert!(cursor.row_number_by_sorted_key(0, &col1[0]).unwrap().is_none());
+        assert!(matches!(
+            cursor.row_number_by_sorted_key(2, &col1[0]),
+            Err(NippyJarError::ColumnOutOfBounds { column: 2, columns: 2 })
+        ));
+    }
+
+    #[test]
+    fn test_cursor_numbered_rows() {
+        let (col1, col2) = test_data(None);
+        let file_path = tempfile::NamedTempFile::new().unwrap();
+        let nippy = freeze_lz4_jar(file_path.path(), &col1, &col2);
+
+        let mut cursor = NippyJarCursor::new(&nippy).unwrap();
+        let mut rows = cursor.rows_in_range(10..20).with_row_numbers();
+        assert_eq!(rows.len(), 10);
+        assert_eq!(rows.next().unwrap().unwrap(), (10, vec![col1[10].clone(), col2[10].clone()]));
+        assert_eq!(
+            rows.next_back().unwrap().unwrap(),
+            (19, vec![col1[19].clone(), col2[19].clone()])
+        );
+        for (expected, row) in (11..19).zip(rows) {
+            let (row_num, values) = row.unwrap();
+            assert_eq!(row_num, expected);
+            assert_eq!(values, vec![col1[row_num].clone(), col2[row_num].clone()]);
+        }
+    }
+
+    #[test]
+    fn test_cursor_first_and_last_row() {
+        let (col1, col2) = test_data(None);
+        let file_path = tempfile::NamedTempFile::new().unwrap();
+        let nippy = freeze_lz4_jar(file_path.path(), &col1, &col2);
+        let last = col1.len() - 1;
+
+        let mut cursor = NippyJarCursor::new(&nippy).unwrap();
+        assert_eq!(cursor.last_row().unwrap().unwrap(), vec![&col1[last][..], &col2[last][..]]);
+        assert!(cursor.next_row().unwrap().is_none());
+        assert_eq!(cursor.first_row().unwrap().unwrap(), vec![&col1[0][..], &col2[0][..]]);
+        assert_eq!(cursor.row_index(), 1);
+
+        let empty_path = tempfile::NamedTempFile::new().unwrap();
+        let empty = freeze_lz4_jar(empty_path.path(), &[], &[]);
+        let mut cursor = NippyJarCursor::new(&empty).unwrap();
+        assert!(cursor.first_row().unwrap().is_none());
+        assert!(cursor.last_row().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_cursor_sampling() {
+        let (col1, col2) = test_data(None);
+        let file_path = tempfile::NamedTempFile::new().unwrap();
+        let nippy = freeze_lz4_jar(file_path.path(), &col1, &col2);
+
+        let mut cursor = NippyJarCursor::new(&nippy).unwrap();
+        let sampled = cursor.rows().step_by(10).collect::<Result<Vec<_>, _>>().unwrap();
+        let expected = (0..col1.len())
+            .step_by(10)
+            .map(|row_num| vec![col1[row_num].clone(), col2[row_num].clone()])
+            .collect::<Vec<_>>();
+        assert_eq!(sampled, expected);
+        // The cursor is left after the last sampled row, not at the end of the range.
+        assert_eq!(cursor.row_index(), 91);
+
+        let mut rows = cursor.rows_in_range(0..col1.len()).with_row_numbers();
+        assert_eq!(rows.nth(5).unwrap().unwrap().0, 5);
+        assert_eq!(rows.nth_back(3).unwrap().unwrap().0, col1.len() - 4);
+        assert_eq!(rows.len(), col1.len() - 10);
+
+        let mut values = cursor.column_iter(1).unwrap();
+        assert_eq!(values.nth(42).unwrap().unwrap(), col2[42]);
+    }
+
+    #[derive(Default)]
+    struct TestPool {
+        buffers: std::sync::Mutex<Vec<Vec<u8>>>,
+        acquired: std::sync::atomic::AtomicUsize,
+    }
+
+    impl BufferPool for TestPool {
+        fn acquire(&self, capacity: usize) -> Vec<u8> {
+            self.acquired.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+            self.buffers.lock().unwrap().pop().unwrap_or_else(|| Vec::with_capacity(capacity))
+        }
+
+        fn release(&self, buffer: Vec<u8>) {
+            self.buffers.lock().unwrap().push(buffer);
+        }
+    }
+
+    /// Allocator which records the largest allocation of the current thread, while it's tracked
+    /// with [`largest_allocation`].
+    struct TrackingAllocator;
+
+    thread_local! {
+        static LARGEST_ALLOCATION: std::cell::Cell<Option<usize>> =
+            const { std::cell::Cell::new(None) };
+    }
+
+    // SAFETY: all allocations are delegated to the system allocator.
+    unsafe impl std::alloc::GlobalAlloc for TrackingAllocator {
+        unsafe fn alloc(&self, layout: std::alloc::Layout) -> *mut u8 {
+            let _ = LARGEST_ALLOCATION.try_with(|largest| {
+                if let Some(size) = largest.get() {
+                    largest.set(Some(size.max(layout.size())));
+                }
+            });
+            // SAFETY: the caller upholds the contract of `GlobalAlloc::alloc`.
+            unsafe { std::alloc::System.alloc(layout) }
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: std::alloc::Layout) {
+            // SAFETY: the caller upholds the contract of `GlobalAlloc::dealloc`.
+            unsafe { std::alloc::System.dealloc(ptr, layout) }
+        }
+    }
+
+    #[global_allocator]
+    static ALLOCATOR: TrackingAllocator = TrackingAllocator;
+
+    /// Returns the size of the largest allocation made by `f` on the current thread.
+    fn largest_allocation(f: impl FnOnce()) -> usize {
+        LARGEST_ALLOCATION.with(|largest| largest.set(Some(0)));
+        f();
+        LARGEST_ALLOCATION.with(|largest| largest.take()).unwrap()
+    }
+
+    #[test]
+    fn test_cursor_buffer_pool() {
+        let (col1, col2) = test_data(None);
+        let file_path = tempfile::NamedTempFile::new().unwrap();
+        let nippy = freeze_lz4_jar(file_path.path(), &col1, &col2);
+        let pool = Arc::new(TestPool::default());
+
+        for _ in 0..3 {
+            let mut cursor = NippyJarCursor::new(&nippy).unwrap().with_buffer_pool(pool.clone());
+            for (v0, v1) in col1.iter().zip(&col2) {
+                assert_eq!(cursor.next_row().unwrap().unwrap(), vec![&v0[..], &v1[..]]);
+            }
+            let clone = cursor.clone();
+            drop((cursor, clone));
+        }
+
+        // Buffers are reused across cursors.
+        assert_eq!(pool.acquired.load(std::sync::atomic::Ordering::Relaxed), 6);
+        assert_eq!(pool.buffers.lock().unwrap().len(), 2);
+
+        // Pooled cursors don't allocate a buffer for the rows outside of the pool.
+        let values = (0..4).map(|i| vec![i; 1 << 16]).collect::<Vec<_>>();
+        let large_path = tempfile::NamedTempFile::new().unwrap();
+        let nippy = freeze_lz4_jar(large_path.path(), &values, &values);
+        let pool = Arc::new(TestPool::default());
+        pool.release(Vec::with_capacity(nippy.max_row_size));
+
+        let largest = largest_allocation(|| {
+            let mut cursor = NippyJarCursor::new(&nippy).unwrap().with_buffer_pool(pool.clone());
+            for value in &values {
+                assert_eq!(cursor.next_row().unwrap().unwrap(), vec![&value[..], &value[..]]);
+            }
+        });
+        assert!(largest < nippy.max_row_size, "{largest} >= {}", nippy.max_row_size);
+        assert_eq!(pool.buffers.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_populated_data_reader() {
+        let (col1, col2) = test_data(None);
+        let file_path = tempfile::NamedTempFile::new().unwrap();
+        let nippy = freeze_lz4_jar(file_path.path(), &col1, &col2);
+
+        let reader = Arc::new(nippy.open_populated_data_reader().unwrap());
+        let mut cursor = NippyJarCursor::with_reader(&nippy, reader).unwrap();
+        for (v0, v1) in col1.iter().zip(&col2) {
+            assert_eq!(cursor.next_row().unwrap().unwrap(), vec![&v0[..], &v1[..]]);
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_file_locking() {
+        let (col1, col2) = test_data(None);
+        let file_path = tempfile::NamedTempFile::new().unwrap();
+        let nippy = freeze_lz4_jar(file_path.path(), &col1, &col2);
+
+        let reader = nippy.open_data_reader().unwrap();
+        reader.try_lock_shared().unwrap();
+        // Shared locks don't conflict with each other.
+        let other_reader = nippy.open_data_reader().unwrap();
+        other_reader.try_lock_shared().unwrap();
+
+        let writer =
+            NippyJarWriter::new(NippyJar::load_without_header(file_path.path()).unwrap()).unwrap();
+        assert!(matches!(writer.try_lock_exclusive(), Err(NippyJarError::FileLocked)));
+
+        drop((reader, other_reader));
+        writer.try_lock_exclusive().unwrap();
+        assert!(matches!(
+            nippy.open_data_reader().unwrap().try_lock_shared(),
+            Err(NippyJarError::FileLocked)
+        ));
+    }
+
+    #[test]
+    fn test_cursor_raw_value() {
+        let (col1, col2) = test_data(None);
+        let num_rows = col1.len() as u64;
+        let file_path = tempfile::NamedTempFile::new().unwrap();
+        let nippy = NippyJar::new_without_header(2, file_path.path())
+            .with_lz4()
+            .with_raw_columns(0b10)
+            .freeze(vec![clone_with_result(&col1), clone_with_result(&col2)], num_rows)
+            .unwrap();
+
+        let mut cursor = NippyJarCursor::new(&nippy).unwrap();
+        let mut end = 0;
+        for row_num in 0..col1.len() {
+            let compressed = cursor.raw_value_by_row_col(row_num, 0).unwrap().unwrap();
+            assert_eq!(compressed.range.start, end);
+            let compressor = nippy.column_compressor(0).unwrap();
+            assert_eq!(compressor.decompress(compressed.data).unwrap(), col1[row_num]);
+            let compressed_end = compressed.range.end;
+
+            let raw = cursor.raw_value_by_row_col(row_num, 1).unwrap().unwrap();
+            assert_eq!(raw.data, col2[row_num]);
+            assert_eq!(raw.range.start, compressed_end);
+            end = raw.range.end;
+        }
+        assert!(cursor.raw_value_by_row_col(col1.len(), 0).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_cursor_cancellation() {
+        let (col1, col2) = test_data(None);
+        let file_path = tempfile::NamedTempFile::new().unwrap();
+        let nippy = freeze_lz4_jar(file_path.path(), &col1, &col2);
+
+        let cancelled = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let mut cursor = NippyJarCursor::new(&nippy).unwrap().with_cancellation(cancelled.clone());
+
+        let mut rows = cursor.rows();
+        for _ in 0..10 {
+            rows.next().unwrap().unwrap();
+        }
+        cancelled.store(true, std::sync::atomic::Ordering::Relaxed);
+        assert!(matches!(rows.next(), Some(Err(NippyJarError::Cancelled))));
+        assert!(rows.next().is_none());
+        assert_eq!(cursor.row_index(), 10);
+
+        cursor.set_cancellation(None);
+        assert_eq!(cursor.next_row().unwrap().unwrap(), vec![&col1[10][..], &col2[10][..]]);
+    }
+
+    #[test]
+    fn test_cursor_value_range() {
+        let (col1, col2) = test_data(None);
+        let num_rows = col1.len() as u64;
+        let file_path = tempfile::NamedTempFile::new().unwrap();
+        let nippy = NippyJar::new_without_header(2, file_path.path())
+            .freeze(vec![clone_with_result(&col1), clone_with_result(&col2)], num_rows)
+            .unwrap();
+        let data = std::fs::read(nippy.data_path()).unwrap();
+
+        let cursor = NippyJarCursor::new(&nippy).unwrap();
+        for row_num in [0, 17, col1.len() - 1] {
+            let range = cursor.value_range(row_num, 0).unwrap().unwrap();
+            assert_eq!(data[range], col1[row_num]);
+            let range = cursor.value_range(row_num, 1).unwrap().unwrap();
+            assert_eq!(data[range], col2[row_num]);
+        }
+        assert_eq!(cursor.row_index(), 0);
+        assert!(cursor.value_range(col1.len(), 0).unwrap().is_none());
+        assert!(matches!(
+            cursor.value_range(0, 2),
+            Err(NippyJarError::ColumnOutOfBounds { column: 2, columns: 2 })
+        ));
+    }
+
+    #[test]
+    fn test_writer_append_row() {
+        let (col1, col2) = test_data(None);
+        let file_path = tempfile::NamedTempFile::new().unwrap();
+
+        let nippy = NippyJar::new_without_header(2, file_path.path()).with_lz4();
+        let mut writer = NippyJarWriter::new(nippy).unwrap();
+        for (v0, v1) in col1.iter().zip(&col2) {
+            writer.append_row(&[v0.as_slice(), v1.as_slice()]).unwrap();
+        }
+
+        // A row with a wrong number of values is rejected without writing anything.
+        assert!(matches!(
+            writer.append_row(&[col1[0].as_slice()]),
+            Err(NippyJarError::ColumnLenMismatch(2, 1))
+        ));
+        assert_eq!(writer.column(), 0);
+
+        let nippy = writer.finish().unwrap();
+        assert_eq!(nippy.rows(), col1.len());
+
+        let loaded_nippy = NippyJar::load_without_header(file_path.path()).unwrap();
+        let mut cursor = NippyJarCursor::new(&loaded_nippy).unwrap();
+        for (v0, v1) in col1.iter().zip(&col2) {
+            assert_eq!(cursor.next_row().unwrap().unwrap(), vec![&v0[..], &v1[..]]);
+        }
+        assert!(cursor.next_row().unwrap().is_none());
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_writer_par_append_rows() {
+        let (col1, col2) = test_data(None);
+        let num_rows = col1.len() as u64;
+        let expected_path = tempfile::NamedTempFile::new().unwrap();
+        let file_path = tempfile::NamedTempFile::new().unwrap();
+
+        let expected = freeze_lz4_jar(expected_path.path(), &col1, &col2);
+
+        let nippy = NippyJar::new_without_header(2, file_path.path()).with_lz4();
+        let mut writer = NippyJarWriter::new(nippy).unwrap();
+        writer
+            .par_append_rows(
+                vec![clone_with_result(&col1), clone_with_result(&col2)],
+                num_rows,
+                NonZeroUsize::new(7).unwrap(),
+            )
+            .unwrap();
+        let nippy = writer.finish().unwrap();
+
+        assert_eq!(nippy.rows(), expected.rows());
+        assert_eq!(nippy.max_row_size, expected.max_row_size);
+        assert_eq!(
+            std::fs::read(nippy.data_path()).unwrap(),
+            std::fs::read(expected.data_path()).unwrap()
+        );
+        assert_eq!(
+            std::fs::read(nippy.offsets_path()).unwrap(),
+            std::fs::read(expected.offsets_path()).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_freeze_resumable() {
+        let (col1, col2) = test_data(None);
+        let num_rows = col1.len() as u64;
+        let checkpoint_interval = NonZeroUsize::new(10).unwrap();
+        let file_path = tempfile::NamedTempFile::new().unwrap();
+
+        // Interrupt the freeze in the middle of the third checkpoint
+        let interrupted = clone_with_result(&col1)
+            .into_iter()
+            .take(25)
+            .chain(std::iter::once(Err("interrupted".into())))
+            .collect::<Vec<_>>();
+        let nippy = NippyJar::new_without_header(2, file_path.path());
+        assert!(nippy
+            .freeze_resumable(
+                vec![interrupted, clone_with_result(&col2)],
+                num_rows,
+                checkpoint_interval
+            )
+            .is_err());
+        assert_eq!(NippyJar::load_without_header(file_path.path()).unwrap().rows(), 20);
+
+        // Resumes from the last checkpoint
+        let nippy = NippyJar::new_without_header(2, file_path.path())
+            .freeze_resumable(
+                vec![clone_with_result(&col1), clone_with_result(&col2)],
+                num_rows,
+                checkpoint_interval,
+            )
+            .unwrap();
+        assert_eq!(nippy.rows(), col1.len());
+
+        let loaded = NippyJar::load_without_header(file_path.path()).unwrap();
+        let mut cursor = NippyJarCursor::new(&loaded).unwrap();
+        for (row, (v1, v2)) in col1.iter().zip(&col2).enumerate() {
+            assert_eq!(
+                cursor.row_by_number(row).unwrap(),
+                Some(vec![v1.as_slice(), v2.as_slice()])
+            );
+        }
+        assert_eq!(cursor.next_row().unwrap(), None);
+    }
+
+    #[test]
+    fn test_writer_max_pending_offsets() {
+        let (col1, col2) = test_data(None);
+        let num_rows = col1.len() as u64;
+        let expected_path = tempfile::NamedTempFile::new().unwrap();
+        let file_path = tempfile::NamedTempFile::new().unwrap();
+
+        let expected = freeze_lz4_jar(expected_path.path(), &col1, &col2);
+
+        let nippy = NippyJar::new_without_header(2, file_path.path()).with_lz4();
+        let mut writer = NippyJarWriter::new(nippy)
+            .unwrap()
+            .with_max_pending_offsets(NonZeroUsize::new(15).unwrap());
+        writer
+            .append_rows(vec![clone_with_result(&col1), clone_with_result(&col2)], num_rows)
+            .unwrap();
+        assert!(writer.offsets().len() < 15);
+
+        // Flushed offsets are discarded if the configuration isn't committed
+        drop(writer);
+        let nippy = NippyJar::new_without_header(2, file_path.path()).with_lz4();
+        let mut writer = NippyJarWriter::new(nippy)
+            .unwrap()
+            .with_max_pending_offsets(NonZeroUsize::new(15).unwrap());
+        assert_eq!(writer.rows(), 0);
+
+        writer
+            .append_rows(vec![clone_with_result(&col1), clone_with_result(&col2)], num_rows)
+            .unwrap();
+        let nippy = writer.finish().unwrap();
+
+        assert_eq!(nippy.rows(), expected.rows());
+        assert_eq!(
+            std::fs::read(nippy.data_path()).unwrap(),
+            std::fs::read(expected.data_path()).unwrap()
+        );
+        assert_eq!(
+            std::fs::read(nippy.offsets_path()).unwrap(),
+            std::fs::read(expected.offsets_path()).unwrap()
+        );
+
+        // Parallel appends are bounded as well
+        #[cfg(feature = "rayon")]
+        {
+            let file_path = tempfile::NamedTempFile::new().unwrap();
+            let nippy = NippyJar::new_without_header(2, file_path.path()).with_lz4();
+            let mut writer = NippyJarWriter::new(nippy)
+                .unwrap()
+                .with_max_pending_offsets(NonZeroUsize::new(15).unwrap());
+            writer
+                .par_append_rows(
+                    vec![clone_with_result(&col1), clone_with_result(&col2)],
+                    num_rows,
+                    NonZeroUsize::new(40).unwrap(),
+                )
+                .unwrap();
+            assert!(writer.offsets().len() < 15);
+
+            let nippy = writer.finish().unwrap();
+            assert_eq!(
+                std::fs::read(nippy.data_path()).unwrap(),
+                std::fs::read(expected.data_path()).unwrap()
+            );
+            assert_eq!(
+                std::fs::read(nippy.offsets_path()).unwrap(),
+                std::fs::read(expected.offsets_path()).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn test_writer_preallocate() {
+        let (col1, col2) = test_data(None);
+        let num_rows = col1.len() as u64;
+        let file_path = tempfile::NamedTempFile::new().unwrap();
+
+        let nippy = NippyJar::new_without_header(2, file_path.path());
+        let mut writer = NippyJarWriter::new(nippy).unwrap();
+        writer.preallocate(1024 * 1024).unwrap();
+        writer
+            .append_rows(vec![clone_with_result(&col1), clone_with_result(&col2)], num_rows)
+            .unwrap();
+        writer.commit().unwrap();
+
+        // Reserved space doesn't change the size of the data file
+        assert_eq!(std::fs::metadata(file_path.path()).unwrap().len(), col1.len() as u64 * 64);
+
+        let loaded = NippyJar::load_without_header(file_path.path()).unwrap();
+        NippyJarChecker::new(loaded).check_consistency().unwrap();
+    }
+
+    #[test]
+    fn test_writer_data_buffer_capacity() {
+        let (col1, col2) = test_data(None);
+        let num_rows = col1.len() as u64;
+        let expected_path = tempfile::NamedTempFile::new().unwrap();
+        let file_path = tempfile::NamedTempFile::new().unwrap();
+
+        let expected = freeze_lz4_jar(expected_path.path(), &col1, &col2);
+
+        let nippy = NippyJar::new_without_header(2, file_path.path()).with_lz4();
+        let mut writer =
+            NippyJarWriter::new(nippy).unwrap().with_data_buffer_capacity(1024 * 1024).unwrap();
+        writer
+            .append_rows(vec![clone_with_result(&col1), clone_with_result(&col2)], num_rows)
+            .unwrap();
+
+        // Everything is still buffered
+        assert_eq!(std::fs::metadata(file_path.path()).unwrap().len(), 0);
+
+        let nippy = writer.finish().unwrap();
+        assert_eq!(
+            std::fs::read(nippy.data_path()).unwrap(),
+            std::fs::read(expected.data_path()).unwrap()
+        );
+        assert_eq!(
+            std::fs::read(nippy.offsets_path()).unwrap(),
+            std::fs::read(expected.offsets_path()).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_freeze_into() {
+        let (col1, col2) = test_data(None);
+        let num_rows = col1.len() as u64;
+        let expected_path = tempfile::NamedTempFile::new().unwrap();
+        let file_path = tempfile::NamedTempFile::new().unwrap();
+
+        let expected = freeze_lz4_jar(expected_path.path(), &col1, &col2);
+
+        let (mut data, mut offsets, mut config) = (Vec::new(), Vec::new(), Vec::new());
+        let nippy = NippyJar::new_without_header(2, file_path.path())
+            .with_lz4()
+            .freeze_into(
+                vec![clone_with_result(&col1), clone_with_result(&col2)],
+                num_rows,
+                &mut data,
+                &mut offsets,
+            )
+            .unwrap();
+        nippy.save_to_writer(&mut config).unwrap();
+
+        // Nothing is written to disk
+        assert!(!nippy.config_path().exists());
+        assert_eq!(std::fs::metadata(file_path.path()).unwrap().len(), 0);
+
+        assert_eq!(data, std::fs::read(expected.data_path()).unwrap());
+        assert_eq!(offsets, std::fs::read(expected.offsets_path()).unwrap());
+
+        let loaded = NippyJar::<()>::load_from_reader(&config[..]).unwrap();
+        assert_eq!(loaded.rows(), expected.rows());
+        assert_eq!(loaded.max_row_size, expected.max_row_size);
+
+        let mut cursor = NippyJarCursor::from_parts(&loaded, &offsets, &data).unwrap();
+        for (v1, v2) in col1.iter().zip(&col2) {
+            assert_eq!(cursor.next_row().unwrap(), Some(vec![v1.as_slice(), v2.as_slice()]));
+        }
+
+        // Offsets pointing past the end of the data are rejected.
+        assert!(matches!(
+            NippyJarCursor::from_parts(&loaded, &offsets, &data[..data.len() - 1]),
+            Err(NippyJarError::OffsetOutOfBounds { index }) if index == offsets.len() - 8
+        ));
+
+        // Jars which already have rows can't be frozen again.
+        let (mut data, mut offsets) = (Vec::new(), Vec::new());
+        assert!(matches!(
+            loaded.freeze_into(
+                vec![clone_with_result(&col1), clone_with_result(&col2)],
+                num_rows,
+                &mut data,
+                &mut offsets,
+            ),
+            Err(NippyJarError::FrozenJar)
+        ));
+        assert!(data.is_empty() && offsets.is_empty());
+    }
+
+    #[test]
+    fn test_validate_freeze() {
+        let (col1, col2) = test_data(None);
+        let num_rows = col1.len() as u64;
+        let file_path = tempfile::NamedTempFile::new().unwrap();
+
+        let nippy = NippyJar::new_without_header(2, file_path.path());
+        nippy
+            .validate_freeze(vec![clone_with_result(&col1), clone_with_result(&col2)], num_rows)
+            .unwrap();
+        assert!(matches!(
+            nippy.validate_freeze(vec![clone_with_result(&col1)], num_rows),
+            Err(NippyJarError::ColumnLenMismatch(2, 1))
+        ));
+        assert!(matches!(
+            nippy.validate_freeze(
+                vec![clone_with_result(&col1), clone_with_result(&col2[..10])],
+                num_rows
+            ),
+            Err(NippyJarError::UnexpectedMissingValue(10, 1))
+        ));
+
+        let mut failing = clone_with_result(&col2);
+        failing[5] = Err("failed".into());
+        assert!(nippy.validate_freeze(vec![clone_with_result(&col1), failing], num_rows).is_err());
+
+        let nippy = NippyJar::new_without_header(2, file_path.path()).with_zstd(true, 5000);
+        assert!(matches!(
+            nippy.validate_freeze(
+                vec![clone_with_result(&col1), clone_with_result(&col2)],
+                num_rows
+            ),
+            Err(NippyJarError::CompressorNotReady)
+        ));
+
+        // Nothing is written to disk
+        assert!(!nippy.config_path().exists());
+        assert!(!nippy.offsets_path().exists());
+        assert_eq!(std::fs::metadata(file_path.path()).unwrap().len(), 0);
+    }
+
+    #[test]
+    fn test_writer_page_cache_eviction() {
+        let (col1, col2) = test_data(None);
+        let num_rows = col1.len() as u64;
+        let file_path = tempfile::NamedTempFile::new().unwrap();
+
+        let nippy = NippyJar::new_without_header(2, file_path.path());
+        let mut writer = NippyJarWriter::new(nippy).unwrap().with_page_cache_eviction(true);
+        writer
+            .append_rows(vec![clone_with_result(&col1), clone_with_result(&col2)], num_rows)
+            .unwrap();
+        writer.commit().unwrap();
+
+        let loaded = NippyJar::load_without_header(file_path.path()).unwrap();
+        let mut cursor = NippyJarCursor::new(&loaded).unwrap();
+        for (v1, v2) in col1.iter().zip(&col2) {
+            assert_eq!(cursor.next_row().unwrap(), Some(vec![v1.as_slice(), v2.as_slice()]));
+        }
+    }
+
+    #[test]
+    fn test_writer_row_offsets() {
+        let (col1, col2) = test_data(None);
+        let num_rows = col1.len() as u64;
+        let file_path = tempfile::NamedTempFile::new().unwrap();
+
+        let nippy = NippyJar::new_without_header(2, file_path.path()).with_lz4();
+        let mut writer = NippyJarWriter::new(nippy).unwrap();
+
+        let mut row_offsets = Vec::new();
+        writer
+            .append_rows_with_row_offsets(
+                vec![clone_with_result(&col1), clone_with_result(&col2)],
+                num_rows,
+                |row, offset| row_offsets.push((row, offset)),
+            )
+            .unwrap();
+        writer.commit().unwrap();
+
+        let loaded = NippyJar::load_without_header(file_path.path()).unwrap();
+        let reader = loaded.open_data_reader().unwrap();
+        assert_eq!(row_offsets.len(), col1.len());
+        for (row, (number, offset)) in row_offsets.into_iter().enumerate() {
+            assert_eq!(number, row);
+            assert_eq!(offset, reader.offset(row * 2).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_writer_abort() {
+        let (col1, col2) = test_data(None);
+        let num_rows = col1.len() as u64;
+        let file_path = tempfile::NamedTempFile::new().unwrap();
+        let data_path = file_path.path().to_path_buf();
+        drop(file_path);
+
+        // Aborting a new jar removes its files
+        let nippy = NippyJar::new_without_header(2, &data_path);
+        let mut writer = NippyJarWriter::new(nippy).unwrap();
+        writer
+            .append_rows(vec![clone_with_result(&col1), clone_with_result(&col2)], num_rows)
+            .unwrap();
+        writer.abort().unwrap();
+        let nippy = NippyJar::new_without_header(2, &data_path);
+        assert!(!data_path.exists());
+        assert!(!nippy.offsets_path().exists());
+        assert!(!nippy.config_path().exists());
+
+        // Aborting an existing jar discards the uncommitted rows
+        let mut writer = NippyJarWriter::new(nippy)
+            .unwrap()
+            .with_max_pending_offsets(NonZeroUsize::new(15).unwrap());
+        writer
+            .append_rows(vec![clone_with_result(&col1[..10]), clone_with_result(&col2[..10])], 10)
+            .unwrap();
+        writer.commit().unwrap();
+        writer
+            .append_rows(
+                vec![clone_with_result(&col1[10..]), clone_with_result(&col2[10..])],
+                num_rows - 10,
+            )
+            .unwrap();
+        writer.abort().unwrap();
+
+        let loaded = NippyJar::load_without_header(&data_path).unwrap();
+        assert_eq!(loaded.rows(), 10);
+        NippyJarChecker::new(NippyJar::load_without_header(&data_path).unwrap())
+            .check_consistency()
+            .unwrap();
+
+        let mut cursor = NippyJarCursor::new(&loaded).unwrap();
+        for (v1, v2) in col1.iter().zip(&col2).take(10) {
+            assert_eq!(cursor.next_row().unwrap(), Some(vec![v1.as_slice(), v2.as_slice()]));
+        }
+        assert_eq!(cursor.next_row().unwrap(), None);
+        drop(cursor);
+        loaded.delete().unwrap();
+    }
+
+    #[test]
+    fn test_writer_checksums() {
+        let (col1, col2) = test_data(None);
+        let file_path = tempfile::NamedTempFile::new().unwrap();
+
+        // Checksums of committed rows are computed when enabling them
+        let nippy = NippyJar::new_without_header(2, file_path.path()).with_lz4();
+        let mut writer = NippyJarWriter::new(nippy).unwrap();
+        writer
+            .append_rows(vec![clone_with_result(&col1[..10]), clone_with_result(&col2[..10])], 10)
+            .unwrap();
+        let nippy = writer.finish().unwrap();
+        assert!(matches!(nippy.verify_checksums(), Err(NippyJarError::MissingFile(_))));
+
+        let mut writer = NippyJarWriter::new(nippy).unwrap().with_checksums().unwrap();
+        writer
+            .append_rows(
+                vec![clone_with_result(&col1[10..50]), clone_with_result(&col2[10..50])],
+                40,
+            )
+            .unwrap();
+        let nippy = writer.finish().unwrap();
+        nippy.verify_checksums().unwrap();
+
+        // Writers keep existing checksums up to date
+        let mut writer = NippyJarWriter::new(nippy).unwrap();
+        writer
+            .append_rows(vec![clone_with_result(&col1[50..]), clone_with_result(&col2[50..])], 50)
+            .unwrap();
+        writer.prune_rows(5).unwrap();
+        let nippy = writer.finish().unwrap();
+        assert_eq!(nippy.rows(), col1.len() - 5);
+        nippy.verify_checksums().unwrap();
+
+        let mut writer = NippyJarWriter::new(nippy).unwrap();
+        writer.prune_rows(20).unwrap();
+        let nippy = writer.finish().unwrap();
+        assert_eq!(
+            std::fs::metadata(nippy.checksums_path()).unwrap().len(),
+            nippy.rows() as u64 * 4
+        );
+        nippy.verify_checksums().unwrap();
+
+        // Corrupted data is detected
+        let reader = nippy.open_data_reader().unwrap();
+        let corrupted_offset = reader.offset(7 * 2).unwrap();
+        drop(reader);
+        let mut data_file = OpenOptions::new().write(true).open(nippy.data_path()).unwrap();
+        data_file.seek(SeekFrom::Start(corrupted_offset)).unwrap();
+        data_file.write_all(&[0xff; 4]).unwrap();
+        drop(data_file);
+        assert!(matches!(
+            nippy.verify_checksums(),
+            Err(NippyJarError::ChecksumMismatch { row: 7 })
+        ));
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn test_writer_append_row_stream() {
+        let (col1, col2) = test_data(None);
+        let file_path = tempfile::NamedTempFile::new().unwrap();
+
+        let rows = col1.iter().zip(&col2).map(|(v1, v2)| vec![v1.clone(), v2.clone()]);
+        let nippy = NippyJar::new_without_header(2, file_path.path());
+        let (writer, appended) = NippyJarWriter::new(nippy)
+            .unwrap()
+            .append_row_stream(
+                futures_util::stream::iter(rows.collect::<Vec<_>>()),
+                NonZeroUsize::new(10).unwrap(),
+                NonZeroUsize::new(4).unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(appended, col1.len());
+        assert!(!writer.is_dirty());
+
+        let loaded = NippyJar::load_without_header(file_path.path()).unwrap();
+        let mut cursor = NippyJarCursor::new(&loaded).unwrap();
+        for (v1, v2) in col1.iter().zip(&col2) {
+            assert_eq!(cursor.next_row().unwrap(), Some(vec![v1.as_slice(), v2.as_slice()]));
+        }
+
+        // Rows with a missing value are rejected
+        let (writer, _) = NippyJarWriter::new(loaded)
+            .unwrap()
+            .append_row_stream(
+                futures_util::stream::iter(Vec::<Vec<Vec<u8>>>::new()),
+                NonZeroUsize::MIN,
+                NonZeroUsize::MIN,
+            )
+            .await
+            .unwrap();
+        assert!(matches!(
+            writer
+                .append_row_stream(
+                    futures_util::stream::iter(vec![vec![col1[0].clone()]]),
+                    NonZeroUsize::MIN,
+                    NonZeroUsize::MIN,
+                )
+                .await,
+            Err(NippyJarError::ColumnLenMismatch(2, 1))
+        ));
+    }
+
+    #[test]
+    fn test_freeze_value_errors() {
+        let (col1, col2) = test_data(None);
+        let num_rows = col1.len() as u64;
+        let file_path = tempfile::NamedTempFile::new().unwrap();
+
+        // Iterators yielding more values than rows
+        let nippy = NippyJar::new_without_header(2, file_path.path());
+        assert!(matches!(
+            nippy.validate_freeze(
+                vec![clone_with_result(&col1), clone_with_result(&col2)],
+                num_rows - 1
+            ),
+            Err(NippyJarError::UnexpectedExtraValue(99, 0))
+        ));
+        let mut longer_col2 = clone_with_result(&col2);
+        longer_col2.push(Ok(col2[0].clone()));
+        assert!(matches!(
+            nippy.freeze(vec![clone_with_result(&col1), longer_col2], num_rows),
+            Err(NippyJarError::UnexpectedExtraValue(100, 1))
+        ));
+
+        // Iterators of unequal lengths
+        let nippy = NippyJar::new_without_header(2, file_path.path());
+        assert!(matches!(
+            nippy.freeze_into(
+                vec![clone_with_result(&col1[..60]), clone_with_result(&col2)],
+                num_rows,
+                Vec::new(),
+                Vec::new()
+            ),
+            Err(NippyJarError::UnexpectedMissingValue(60, 0))
+        ));
+
+        // Iterators failing to produce a value
+        let mut failing = clone_with_result(&col2);
+        failing[42] = Err("failed".into());
+        let mut writer =
+            NippyJarWriter::new(NippyJar::new_without_header(2, file_path.path())).unwrap();
+        assert!(matches!(
+            writer.append_rows(vec![clone_with_result(&col1), failing], num_rows),
+            Err(NippyJarError::ColumnValue { row: 42, column: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn test_writer_progress() {
+        let (col1, col2) = test_data(None);
+        let num_rows = col1.len() as u64;
+        let file_path = tempfile::NamedTempFile::new().unwrap();
+
+        let nippy = NippyJar::new_without_header(2, file_path.path());
+        let mut writer = NippyJarWriter::new(nippy).unwrap();
+        assert_eq!(writer.progress(), WriteProgress { rows: 0, column: 0, data_size: 0 });
+
+        let mut reports = Vec::new();
+        writer
+            .append_rows_with_progress(
+                vec![clone_with_result(&col1), clone_with_result(&col2)],
+                num_rows,
+                |progress| reports.push(progress),
+            )
+            .unwrap();
+
+        assert_eq!(reports.len(), col1.len() * 2);
+        assert_eq!(reports[0], WriteProgress { rows: 0, column: 1, data_size: 32 });
+        assert_eq!(reports[1], WriteProgress { rows: 1, column: 0, data_size: 64 });
+        assert_eq!(
+            reports.last(),
+            Some(&WriteProgress { rows: col1.len(), column: 0, data_size: col1.len() as u64 * 64 })
+        );
+    }
+
+    #[test]
+    fn test_cursor_reverse_iteration() {
+        let (col1, col2) = test_data(None);
+        let file_path = tempfile::NamedTempFile::new().unwrap();
+        let nippy = freeze_lz4_jar(file_path.path(), &col1, &col2);
+        let num_rows = col1.len();
+
+        let mut cursor = NippyJarCursor::new(&nippy).unwrap();
+        assert!(cursor.prev_row().unwrap().is_none());
+
+        // Walk backwards from the end of the jar.
+        cursor.row_by_number(num_rows + 10).unwrap();
+        for row_num in (0..num_rows).rev() {
+            let row = cursor.prev_row().unwrap().unwrap();
+            assert_eq!(row, vec![&col1[row_num][..], &col2[row_num][..]]);
+            assert_eq!(cursor.row_index(), row_num as u64);
+        }
+        assert!(cursor.prev_row().unwrap().is_none());
+
+        // `prev_row` leaves the cursor on the returned row.
+        cursor.row_by_number(5).unwrap();
+        assert_eq!(cursor.prev_row().unwrap().unwrap(), vec![&col1[5][..], &col2[5][..]]);
+        assert_eq!(cursor.next_row().unwrap().unwrap(), vec![&col1[5][..], &col2[5][..]]);
+
+        // Latest N rows
+        cursor.reset();
+        let latest = cursor.rows().rev().take(3).map(Result::unwrap).collect::<Vec<_>>();
+        assert_eq!(
+            latest,
+            (num_rows - 3..num_rows)
+                .rev()
+                .map(|row_num| vec![col1[row_num].clone(), col2[row_num].clone()])
+                .collect::<Vec<_>>()
+        );
+
+        // Both ends
+        cursor.reset();
+        let mut rows = cursor.rows();
+        assert_eq!(rows.next().unwrap().unwrap()[0], col1[0]);
+        assert_eq!(rows.next_back().unwrap().unwrap()[0], col1[num_rows - 1]);
+        assert_eq!(rows.len(), num_rows - 2);
+    }
+
+    #[test]
+    fn test_cursor_rows_in_range() {
+        let (col1, col2) = test_data(None);
+        let file_path = tempfile::NamedTempFile::new().unwrap();
+        let nippy = freeze_lz4_jar(file_path.path(), &col1, &col2);
+        let num_rows = col1.len();
+
+        let mut cursor = NippyJarCursor::new(&nippy).unwrap();
+        for range in [10..20, 0..1, 95..num_rows + 10, 30..30, num_rows..num_rows + 1] {
+            let rows = cursor.rows_in_range(range.clone()).map(Result::unwrap).collect::<Vec<_>>();
+            let expected = (range.start..range.end.min(num_rows))
+                .map(|row_num| vec![col1[row_num].clone(), col2[row_num].clone()])
+                .collect::<Vec<_>>();
+            assert_eq!(rows, expected);
+        }
+    }
+
+    #[test]
+    fn test_cursor_rows_by_numbers() {
+        let (col1, col2) = test_data(None);
+        let file_path = tempfile::NamedTempFile::new().unwrap();
+        let nippy = freeze_lz4_jar(file_path.path(), &col1, &col2);
+
+        let mut cursor = NippyJarCursor::new(&nippy).unwrap();
+        let numbers = [42, 7, 99, 7, 1000, 0, 42];
+        let rows = cursor.rows_by_numbers(&numbers).unwrap();
+        assert_eq!(rows.len(), numbers.len());
+        for (row, row_num) in rows.into_iter().zip(numbers) {
+            let expected =
+                (row_num < col1.len()).then(|| vec![col1[row_num].clone(), col2[row_num].clone()]);
+            assert_eq!(row, expected);
+        }
+        assert!(cursor.rows_by_numbers(&[]).unwrap().is_empty());
+    }
 }
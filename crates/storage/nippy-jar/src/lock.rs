@@ -0,0 +1,21 @@
+use crate::NippyJarError;
+use std::{fs::File, os::unix::io::AsRawFd};
+
+/// Takes an advisory lock (`flock`) on `file` without blocking, which is shared unless
+/// `exclusive`.
+///
+/// The lock is held until every handle of the open file is closed. Fails with
+/// [`NippyJarError::FileLocked`] if a conflicting lock is held through another open file.
+pub(crate) fn try_lock(file: &File, exclusive: bool) -> Result<(), NippyJarError> {
+    let operation = if exclusive { libc::LOCK_EX } else { libc::LOCK_SH };
+    // SAFETY: The descriptor is valid as long as `file` is alive.
+    if unsafe { libc::flock(file.as_raw_fd(), operation | libc::LOCK_NB) } == 0 {
+        return Ok(())
+    }
+
+    let err = std::io::Error::last_os_error();
+    if err.kind() == std::io::ErrorKind::WouldBlock {
+        return Err(NippyJarError::FileLocked)
+    }
+    Err(err.into())
+}
@@ -0,0 +1,78 @@
+use crate::{
+    cursor::CursorReader, DataReader, NippyJar, NippyJarCursor, NippyJarError, NippyJarHeader,
+};
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+use std::{path::Path, sync::Arc};
+
+/// A loaded [`NippyJar`] together with its memory-mapped data, which can be cheaply cloned and
+/// shared across threads.
+///
+/// The data and offsets files are only opened and mapped once, so creating a cursor with
+/// [`Self::cursor`] does not touch the file system. This allows serving many concurrent readers
+/// over one jar, with a short-lived cursor per request.
+pub struct SharedNippyJar<H = ()> {
+    /// Jar configuration.
+    jar: Arc<NippyJar<H>>,
+    /// Memory-mapped data and offsets files.
+    reader: Arc<DataReader>,
+}
+
+impl<H: NippyJarHeader> std::fmt::Debug for SharedNippyJar<H> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SharedNippyJar").field("jar", &self.jar).finish_non_exhaustive()
+    }
+}
+
+impl<H> Clone for SharedNippyJar<H> {
+    fn clone(&self) -> Self {
+        Self { jar: Arc::clone(&self.jar), reader: Arc::clone(&self.reader) }
+    }
+}
+
+impl<H: NippyJarHeader> SharedNippyJar<H> {
+    /// Opens the data reader of `jar` and returns a [`SharedNippyJar`].
+    pub fn new(jar: impl Into<Arc<NippyJar<H>>>) -> Result<Self, NippyJarError> {
+        let jar = jar.into();
+        let reader = Arc::new(jar.open_data_reader()?);
+        Ok(Self { jar, reader })
+    }
+
+    /// Loads the [`NippyJar`] located at `path` and returns a [`SharedNippyJar`].
+    pub fn load(path: &Path) -> Result<Self, NippyJarError> {
+        Self::new(NippyJar::load(path)?)
+    }
+
+    /// Returns a reference to the shared [`NippyJar`].
+    pub const fn jar(&self) -> &Arc<NippyJar<H>> {
+        &self.jar
+    }
+
+    /// Returns a reference to the shared [`DataReader`].
+    pub const fn data_reader(&self) -> &Arc<DataReader> {
+        &self.reader
+    }
+
+    /// Creates a new [`NippyJarCursor`] over the shared jar and data.
+    pub fn cursor(&self) -> NippyJarCursor<'_, H> {
+        NippyJarCursor::with_cursor_reader(&self.jar, CursorReader::Mmap(Arc::clone(&self.reader)))
+    }
+
+    /// Returns a parallel iterator over the owned rows of the jar.
+    ///
+    /// The rows are split across the rayon thread pool, and each split reads with its own cursor.
+    /// The iterator is indexed, so collecting it preserves the order of the rows.
+    #[cfg(feature = "rayon")]
+    pub fn par_rows(
+        &self,
+    ) -> impl IndexedParallelIterator<Item = Result<crate::Row, NippyJarError>> + '_ {
+        (0..self.jar.rows).into_par_iter().map_init(
+            || self.cursor(),
+            |cursor, row| {
+                cursor
+                    .row_by_number(row)
+                    .map(|row| crate::cursor::to_owned_row(row.expect("row is within range")))
+            },
+        )
+    }
+}
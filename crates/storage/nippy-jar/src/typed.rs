@@ -0,0 +1,54 @@
+use crate::{ColumnResult, NippyJarCursor, NippyJarError, NippyJarHeader};
+use std::marker::PhantomData;
+
+/// A type which can be decoded from the column values of a [`NippyJar`](crate::NippyJar) row.
+pub trait RowDecode: Sized {
+    /// Decodes a row from its column values, in column order.
+    fn decode_row(row: &[&[u8]]) -> ColumnResult<Self>;
+}
+
+/// Wrapper around [`NippyJarCursor`] which decodes every row it reads into `T`.
+pub struct TypedCursor<'a, T, H = ()> {
+    /// Cursor used to read the rows.
+    cursor: NippyJarCursor<'a, H>,
+    /// Marker for the row type decoded by this cursor.
+    _row: PhantomData<T>,
+}
+
+impl<T, H: NippyJarHeader> std::fmt::Debug for TypedCursor<'_, T, H> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TypedCursor").field("cursor", &self.cursor).finish()
+    }
+}
+
+impl<'a, T: RowDecode, H: NippyJarHeader> TypedCursor<'a, T, H> {
+    /// Creates a new instance of [`TypedCursor`] which reads with the given cursor.
+    pub const fn new(cursor: NippyJarCursor<'a, H>) -> Self {
+        Self { cursor, _row: PhantomData }
+    }
+
+    /// Returns a mutable reference to the underlying cursor.
+    pub const fn cursor_mut(&mut self) -> &mut NippyJarCursor<'a, H> {
+        &mut self.cursor
+    }
+
+    /// Returns the underlying cursor.
+    pub fn into_inner(self) -> NippyJarCursor<'a, H> {
+        self.cursor
+    }
+
+    /// Returns a decoded row by its number.
+    pub fn row_by_number(&mut self, row: usize) -> Result<Option<T>, NippyJarError> {
+        self.cursor.row_by_number(row)?.map(|row| decode(&row)).transpose()
+    }
+
+    /// Returns the current decoded row and advances the cursor.
+    pub fn next_row(&mut self) -> Result<Option<T>, NippyJarError> {
+        self.cursor.next_row()?.map(|row| decode(&row)).transpose()
+    }
+}
+
+/// Decodes a row, converting the decoding error into [`NippyJarError::Internal`].
+fn decode<T: RowDecode>(row: &[&[u8]]) -> Result<T, NippyJarError> {
+    T::decode_row(row).map_err(NippyJarError::Internal)
+}
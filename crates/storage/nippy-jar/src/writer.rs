@@ -1,16 +1,23 @@
-use crate::{
-    compression::Compression, ColumnResult, NippyJar, NippyJarChecker, NippyJarError,
-    NippyJarHeader,
-};
+#[cfg(feature = "rayon")]
+use crate::next_column_value;
+use crate::{ColumnResult, NippyJar, NippyJarChecker, NippyJarError, NippyJarHeader};
 use std::{
     fs::{File, OpenOptions},
     io::{BufWriter, Read, Seek, SeekFrom, Write},
+    num::NonZeroUsize,
     path::Path,
 };
 
 /// Size of one offset in bytes.
 pub(crate) const OFFSET_SIZE_BYTES: u8 = 8;
 
+/// Size of the checksum of one row in bytes.
+pub(crate) const CHECKSUM_SIZE_BYTES: u64 = 4;
+
+/// Default maximum number of uncommitted offsets held in memory, before they're flushed to the
+/// offsets file.
+const DEFAULT_MAX_PENDING_OFFSETS: usize = 1_000_000;
+
 /// Writer of [`NippyJar`]. Handles table data and offsets only.
 ///
 /// Table data is written directly to disk, while offsets and configuration need to be flushed by
@@ -31,18 +38,25 @@ pub struct NippyJarWriter<H: NippyJarHeader = ()> {
     jar: NippyJar<H>,
     /// File handle to where the data is stored.
     data_file: BufWriter<File>,
+    /// Writes the values to `data_file`, tracking its size (including buffered data) to avoid
+    /// seeking, which flushes `data_file`.
+    values: ValueWriter,
     /// File handle to where the offsets are stored.
     offsets_file: BufWriter<File>,
-    /// Temporary buffer to reuse when compressing data.
-    tmp_buf: Vec<u8>,
-    /// Used to find the maximum uncompressed size of a row in a jar.
-    uncompressed_row_size: usize,
     /// Partial offset list which hasn't been flushed to disk.
     offsets: Vec<u64>,
-    /// Column where writer is going to write next.
-    column: usize,
+    /// Maximum length of `offsets`, before it's flushed to the offsets file.
+    max_pending_offsets: usize,
     /// Whether the writer has changed data that needs to be committed.
     dirty: bool,
+    /// Whether to evict the data file from the page cache once it's synced to disk.
+    evict_page_cache: bool,
+    /// Whether the jar files were created by this writer.
+    is_created: bool,
+    /// File handle to where the row checksums are stored, if enabled.
+    checksums_file: Option<BufWriter<File>>,
+    /// Checksums of the complete rows which haven't been flushed to disk.
+    checksums: Vec<u8>,
 }
 
 impl<H: NippyJarHeader> NippyJarWriter<H> {
@@ -70,17 +84,26 @@ impl<H: NippyJarHeader> NippyJarWriter<H> {
             (jar, data_file.expect("qed"), offsets_file.expect("qed"))
         };
 
+        let data_size = data_file.get_ref().metadata()?.len();
+
         let mut writer = Self {
             jar,
             data_file,
+            values: ValueWriter::new(data_size),
             offsets_file,
-            tmp_buf: Vec::with_capacity(1_000_000),
-            uncompressed_row_size: 0,
-            offsets: Vec::with_capacity(1_000_000),
-            column: 0,
+            offsets: Vec::with_capacity(DEFAULT_MAX_PENDING_OFFSETS),
+            max_pending_offsets: DEFAULT_MAX_PENDING_OFFSETS,
             dirty: false,
+            evict_page_cache: false,
+            is_created,
+            checksums_file: None,
+            checksums: Vec::new(),
         };
 
+        if writer.jar.checksums_path().exists() {
+            writer.open_checksums_file()?;
+        }
+
         if !is_created {
             // Commit any potential heals done above.
             writer.commit()?;
@@ -89,6 +112,132 @@ impl<H: NippyJarHeader> NippyJarWriter<H> {
         Ok(writer)
     }
 
+    /// Sets the maximum number of uncommitted offsets held in memory. Defaults to `1_000_000`.
+    ///
+    /// Once reached, offsets are flushed to the offsets file without committing the configuration,
+    /// so memory stays bounded when appending many rows before a commit. Until the next commit,
+    /// readers ignore them, and [`NippyJarChecker`] discards them after an unclean shutdown.
+    pub const fn with_max_pending_offsets(mut self, max_pending_offsets: NonZeroUsize) -> Self {
+        self.max_pending_offsets = max_pending_offsets.get();
+        self
+    }
+
+    /// Sets the capacity of the buffer used for writing to the data file. Defaults to the capacity
+    /// of [`BufWriter`].
+    ///
+    /// Larger buffers reduce the number of write syscalls when appending many small values.
+    pub fn with_data_buffer_capacity(mut self, capacity: usize) -> Result<Self, NippyJarError> {
+        let data_file = self.data_file.into_inner().map_err(|err| err.into_error())?;
+        self.data_file = BufWriter::with_capacity(capacity, data_file);
+        Ok(self)
+    }
+
+    /// Writes a checksum of every appended row to the checksums file, which can be verified with
+    /// [`NippyJar::verify_checksums`]. Checksums of already committed rows are computed from the
+    /// data file.
+    ///
+    /// Once a jar has a checksums file, every writer keeps it up to date. Fails with
+    /// [`NippyJarError::InconsistentState`] if there are uncommitted changes.
+    pub fn with_checksums(mut self) -> Result<Self, NippyJarError> {
+        if self.checksums_file.is_none() {
+            if self.dirty {
+                return Err(NippyJarError::InconsistentState)
+            }
+            self.open_checksums_file()?;
+        }
+        Ok(self)
+    }
+
+    /// Opens or creates the checksums file, making sure it has exactly one checksum per committed
+    /// row.
+    fn open_checksums_file(&mut self) -> Result<(), NippyJarError> {
+        let mut checksums_file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(self.jar.checksums_path())?;
+
+        let rows = self.jar.rows as u64;
+        let checksummed_rows = checksums_file.metadata()?.len() / CHECKSUM_SIZE_BYTES;
+        if checksummed_rows > rows {
+            // Happened during an appending job which wasn't committed
+            checksums_file.set_len(rows * CHECKSUM_SIZE_BYTES)?;
+        } else if checksummed_rows < rows {
+            let reader = self.jar.open_data_reader()?;
+            checksums_file.set_len(checksummed_rows * CHECKSUM_SIZE_BYTES)?;
+            checksums_file.seek(SeekFrom::End(0))?;
+
+            let mut checksums = BufWriter::new(&mut checksums_file);
+            for row in checksummed_rows as usize..rows as usize {
+                let start = reader.offset(row * self.jar.columns)? as usize;
+                let end = reader.offset((row + 1) * self.jar.columns)? as usize;
+                checksums.write_all(&crc32fast::hash(reader.data(start..end)).to_le_bytes())?;
+            }
+            checksums.flush()?;
+        }
+        checksums_file.sync_all()?;
+        checksums_file.seek(SeekFrom::End(0))?;
+
+        self.checksums_file = Some(BufWriter::new(checksums_file));
+        self.values.row_hasher = Some(crc32fast::Hasher::new());
+        Ok(())
+    }
+
+    /// Evicts the data file from the OS page cache on every commit, once it has been synced to
+    /// disk. Only supported on Linux.
+    ///
+    /// Keeps large appends from flushing the pages of other files out of the page cache. Pages
+    /// which are mapped by a reader stay cached. This is used instead of `O_DIRECT`, which would
+    /// require every append to be aligned to the block size.
+    pub const fn with_page_cache_eviction(mut self, evict_page_cache: bool) -> Self {
+        self.evict_page_cache = evict_page_cache;
+        self
+    }
+
+    /// Reserves disk space for `additional` bytes past the current end of the data file, without
+    /// changing its size.
+    ///
+    /// Reduces fragmentation when the size of the appended data can be estimated upfront, and makes
+    /// a full disk fail here instead of in the middle of a long append. Does nothing if the
+    /// platform or filesystem doesn't support `fallocate`.
+    pub fn preallocate(&self, additional: u64) -> Result<(), NippyJarError> {
+        #[cfg(target_os = "linux")]
+        {
+            use std::os::unix::io::AsRawFd;
+
+            // SAFETY: The descriptor is valid as long as `self.data_file` is alive.
+            let result = unsafe {
+                libc::fallocate(
+                    self.data_file.get_ref().as_raw_fd(),
+                    libc::FALLOC_FL_KEEP_SIZE,
+                    self.values.data_size as libc::off_t,
+                    additional as libc::off_t,
+                )
+            };
+            if result != 0 {
+                let err = std::io::Error::last_os_error();
+                if err.raw_os_error() != Some(libc::EOPNOTSUPP) {
+                    return Err(err.into())
+                }
+            }
+        }
+        #[cfg(not(target_os = "linux"))]
+        let _ = additional;
+
+        Ok(())
+    }
+
+    /// Takes an exclusive advisory lock on the data file, held until the writer is dropped.
+    ///
+    /// Fails with [`NippyJarError::FileLocked`] if a reader holds a lock taken with
+    /// [`DataReader::try_lock_shared`](crate::DataReader::try_lock_shared), or another writer
+    /// holds this lock.
+    #[cfg(unix)]
+    pub fn try_lock_exclusive(&self) -> Result<(), NippyJarError> {
+        crate::lock::try_lock(self.data_file.get_ref(), true)
+    }
+
     /// Returns a reference to `H` of [`NippyJar`]
     pub const fn user_header(&self) -> &H {
         &self.jar.user_header
@@ -123,6 +272,41 @@ impl<H: NippyJarHeader> NippyJarWriter<H> {
         self.jar
     }
 
+    /// Discards everything appended since the last commit, restoring the data and offsets files to
+    /// their committed state.
+    ///
+    /// If the jar was created by this writer and no rows have been committed, its files are removed
+    /// instead.
+    pub fn abort(self) -> Result<(), NippyJarError> {
+        let Self { jar, data_file, offsets_file, is_created, checksums_file, .. } = self;
+
+        // Buffered data is dropped without being written
+        drop(data_file.into_parts());
+        drop(offsets_file.into_parts());
+        let checksums_file = checksums_file.map(|file| file.into_parts().0);
+
+        let committed = NippyJar::<H>::load(jar.data_path())?;
+        if is_created && committed.rows == 0 {
+            return committed.delete()
+        }
+
+        if let Some(checksums_file) = checksums_file {
+            checksums_file.set_len(committed.rows as u64 * CHECKSUM_SIZE_BYTES)?;
+            checksums_file.sync_all()?;
+        }
+
+        let mut checker = NippyJarChecker::new(committed);
+        checker.ensure_consistency()?;
+        if let Some(data_file) = &checker.data_file {
+            data_file.get_ref().sync_all()?;
+        }
+        if let Some(offsets_file) = &checker.offsets_file {
+            offsets_file.get_ref().sync_all()?;
+        }
+
+        Ok(())
+    }
+
     fn create_or_open_files(
         data: &Path,
         offsets: &Path,
@@ -169,6 +353,19 @@ impl<H: NippyJarHeader> NippyJarWriter<H> {
         &mut self,
         column_values_per_row: Vec<impl IntoIterator<Item = ColumnResult<impl AsRef<[u8]>>>>,
         num_rows: u64,
+    ) -> Result<(), NippyJarError> {
+        self.append_rows_with_progress(column_values_per_row, num_rows, |_| {})
+    }
+
+    /// Appends rows to data file like [`Self::append_rows`], calling `on_progress` after every
+    /// appended value.
+    ///
+    /// Allows reporting the progress of long running jobs, which write many rows at once.
+    pub fn append_rows_with_progress(
+        &mut self,
+        column_values_per_row: Vec<impl IntoIterator<Item = ColumnResult<impl AsRef<[u8]>>>>,
+        num_rows: u64,
+        mut on_progress: impl FnMut(WriteProgress),
     ) -> Result<(), NippyJarError> {
         let mut column_iterators = column_values_per_row
             .into_iter()
@@ -181,6 +378,7 @@ impl<H: NippyJarHeader> NippyJarWriter<H> {
 
             for mut column_iter in column_iterators {
                 self.append_column(column_iter.next())?;
+                on_progress(self.progress());
 
                 iterators.push(column_iter);
             }
@@ -191,6 +389,192 @@ impl<H: NippyJarHeader> NippyJarWriter<H> {
         Ok(())
     }
 
+    /// Appends rows to data file like [`Self::append_rows`], compressing the values on the rayon
+    /// thread pool.
+    ///
+    /// Rows are read from the source iterators in batches of `batch_size`. Each batch is
+    /// compressed in parallel and then written in order, so the resulting data file is identical to
+    /// the one written by [`Self::append_rows`]. `fn commit()` should be called to flush offsets
+    /// and config to disk.
+    #[cfg(feature = "rayon")]
+    pub fn par_append_rows<V: AsRef<[u8]> + Sync>(
+        &mut self,
+        column_values_per_row: Vec<impl IntoIterator<Item = ColumnResult<V>>>,
+        num_rows: u64,
+        batch_size: NonZeroUsize,
+    ) -> Result<(), NippyJarError> {
+        use rayon::prelude::*;
+
+        let mut column_iterators =
+            column_values_per_row.into_iter().map(|v| v.into_iter()).collect::<Vec<_>>();
+        let columns = column_iterators.len();
+
+        let mut remaining = num_rows as usize;
+        let mut batch = Vec::with_capacity(batch_size.get().min(remaining) * columns);
+        while remaining > 0 {
+            let batch_rows = batch_size.get().min(remaining);
+            for row in 0..batch_rows {
+                for (column, column_iter) in column_iterators.iter_mut().enumerate() {
+                    batch.push(next_column_value(
+                        column_iter,
+                        (self.jar.rows + row) as u64,
+                        column,
+                    )?);
+                }
+            }
+
+            let first_column = self.values.column;
+            let compressed = batch
+                .par_iter()
+                .enumerate()
+                .map(|(index, value)| {
+                    let column = (first_column + index) % self.jar.columns;
+                    self.jar
+                        .column_compressor(column)
+                        .map(|compression| compression.compress_column(column, value.as_ref()))
+                        .transpose()
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+
+            for (value, compressed) in batch.iter().zip(compressed) {
+                let value = value.as_ref();
+                self.write_stored_column(value.len(), compressed.as_deref().unwrap_or(value))?;
+            }
+
+            batch.clear();
+            remaining -= batch_rows;
+        }
+
+        Ok(())
+    }
+
+    /// Appends rows to data file like [`Self::append_rows`], calling `on_row` with the row number
+    /// and the offset of its first value in the data file, once the row has been appended.
+    ///
+    /// Allows building auxiliary indexes over the data file in the same pass.
+    pub fn append_rows_with_row_offsets(
+        &mut self,
+        column_values_per_row: Vec<impl IntoIterator<Item = ColumnResult<impl AsRef<[u8]>>>>,
+        num_rows: u64,
+        mut on_row: impl FnMut(usize, u64),
+    ) -> Result<(), NippyJarError> {
+        let mut row_offset = self.values.data_size;
+        self.append_rows_with_progress(column_values_per_row, num_rows, |progress| {
+            if progress.column == 0 {
+                on_row(progress.rows - 1, row_offset);
+                row_offset = progress.data_size;
+            }
+        })
+    }
+
+    /// Returns the current [`WriteProgress`] of the writer.
+    pub const fn progress(&self) -> WriteProgress {
+        WriteProgress {
+            rows: self.jar.rows,
+            column: self.values.column,
+            data_size: self.values.data_size,
+        }
+    }
+
+    /// Appends rows from a source which may still be producing them (e.g. the receiving end of a
+    /// bounded channel), committing every `commit_interval` rows and once the source is exhausted.
+    ///
+    /// Every commit leaves a loadable jar behind, so readers can access the earlier rows while the
+    /// later ones are still being written. Returns the number of appended rows.
+    pub fn append_rows_with_commits<V: AsRef<[u8]>>(
+        &mut self,
+        rows: impl IntoIterator<Item = impl IntoIterator<Item = V>>,
+        commit_interval: NonZeroUsize,
+    ) -> Result<usize, NippyJarError> {
+        let mut appended = 0;
+        for row in rows {
+            self.append_row_values(row)?;
+            appended += 1;
+
+            if appended % commit_interval.get() == 0 {
+                self.commit()?;
+            }
+        }
+
+        if self.dirty {
+            self.commit()?;
+        }
+
+        Ok(appended)
+    }
+
+    /// Appends the rows of an asynchronous stream like [`Self::append_rows_with_commits`], and
+    /// returns the writer alongside the number of appended rows.
+    ///
+    /// Writing runs on the blocking thread pool of tokio with [`tokio::task::spawn_blocking`]. Rows
+    /// are handed over through a channel holding at most `buffer` rows, so a fast stream waits for
+    /// the disk instead of piling rows up in memory.
+    #[cfg(feature = "tokio")]
+    pub async fn append_row_stream<V: AsRef<[u8]>>(
+        self,
+        rows: impl futures_util::Stream<Item = impl IntoIterator<Item = V> + Send + 'static>,
+        commit_interval: NonZeroUsize,
+        buffer: NonZeroUsize,
+    ) -> Result<(Self, usize), NippyJarError> {
+        use futures_util::StreamExt;
+
+        let (tx, mut rx) = tokio::sync::mpsc::channel(buffer.get());
+        let handle = tokio::task::spawn_blocking(move || {
+            let mut writer = self;
+            let appended = writer.append_rows_with_commits(
+                std::iter::from_fn(|| rx.blocking_recv()),
+                commit_interval,
+            );
+            appended.map(|appended| (writer, appended))
+        });
+
+        let mut rows = std::pin::pin!(rows);
+        while let Some(row) = rows.next().await {
+            // The writer stopped receiving because it failed, which is returned below
+            if tx.send(row).await.is_err() {
+                break
+            }
+        }
+        drop(tx);
+
+        handle.await.map_err(|err| NippyJarError::Internal(Box::new(err)))?
+    }
+
+    /// Appends a single row, with one value per column. `fn commit()` should be called to flush
+    /// offsets and config to disk, or `fn finish()` once the last row has been appended.
+    ///
+    /// Nothing is written if the number of values doesn't match the number of columns.
+    pub fn append_row<V: AsRef<[u8]>>(&mut self, row: &[V]) -> Result<(), NippyJarError> {
+        if row.len() != self.jar.columns {
+            return Err(NippyJarError::ColumnLenMismatch(self.jar.columns, row.len()))
+        }
+        self.append_row_values(row)
+    }
+
+    /// Commits all appended rows and returns the resulting [`NippyJar`].
+    pub fn finish(mut self) -> Result<NippyJar<H>, NippyJarError> {
+        self.commit()?;
+        Ok(self.into_jar())
+    }
+
+    /// Appends all column values of a single row, making sure that it has exactly one value per
+    /// column. Nothing is written if it doesn't.
+    fn append_row_values<V: AsRef<[u8]>>(
+        &mut self,
+        row: impl IntoIterator<Item = V>,
+    ) -> Result<(), NippyJarError> {
+        let row = row.into_iter().collect::<Vec<_>>();
+        if row.len() != self.jar.columns {
+            return Err(NippyJarError::ColumnLenMismatch(self.jar.columns, row.len()))
+        }
+
+        for value in row {
+            self.append_column(Some(Ok(value)))?;
+        }
+
+        Ok(())
+    }
+
     /// Appends a column to data file. `fn commit()` should be called to flush offsets and config to
     /// disk.
     pub fn append_column(
@@ -200,50 +584,71 @@ impl<H: NippyJarHeader> NippyJarWriter<H> {
         self.dirty = true;
 
         match column {
-            Some(Ok(value)) => {
-                if self.offsets.is_empty() {
-                    // Represents the offset of the soon to be appended data column
-                    self.offsets.push(self.data_file.stream_position()?);
-                }
-
-                let written = self.write_column(value.as_ref())?;
-
-                // Last offset represents the size of the data file if no more data is to be
-                // appended. Otherwise, represents the offset of the next data item.
-                self.offsets.push(self.offsets.last().expect("qed") + written as u64);
-            }
+            Some(Ok(value)) => self.write_column(value.as_ref())?,
             None => {
                 return Err(NippyJarError::UnexpectedMissingValue(
                     self.jar.rows as u64,
-                    self.column as u64,
+                    self.values.column as u64,
                 ))
             }
-            Some(Err(err)) => return Err(err.into()),
+            Some(Err(source)) => {
+                return Err(NippyJarError::ColumnValue {
+                    row: self.jar.rows as u64,
+                    column: self.values.column as u64,
+                    source,
+                })
+            }
         }
 
         Ok(())
     }
 
-    /// Writes column to data file. If it's the last column of the row, call `finalize_row()`
-    fn write_column(&mut self, value: &[u8]) -> Result<usize, NippyJarError> {
-        self.uncompressed_row_size += value.len();
-        let len = if let Some(compression) = &self.jar.compressor {
-            let before = self.tmp_buf.len();
-            let len = compression.compress_to(value, &mut self.tmp_buf)?;
-            self.data_file.write_all(&self.tmp_buf[before..before + len])?;
-            len
-        } else {
-            self.data_file.write_all(value)?;
-            value.len()
-        };
+    /// Writes column to data file, compressing it if its column is compressed.
+    fn write_column(&mut self, value: &[u8]) -> Result<(), NippyJarError> {
+        self.begin_column();
+        self.values.write_value(&self.jar, value, &mut self.data_file)?;
+        self.end_column()
+    }
 
-        self.column += 1;
+    /// Writes a column value, as stored on disk, to data file.
+    #[cfg(feature = "rayon")]
+    fn write_stored_column(
+        &mut self,
+        uncompressed_len: usize,
+        value: &[u8],
+    ) -> Result<(), NippyJarError> {
+        self.begin_column();
+        self.values.write_stored_value(uncompressed_len, value, &mut self.data_file)?;
+        self.end_column()
+    }
+
+    /// Marks the writer as dirty before a column value is written.
+    fn begin_column(&mut self) {
+        self.dirty = true;
+
+        if self.offsets.is_empty() {
+            // Represents the offset of the soon to be appended data column
+            self.offsets.push(self.values.data_size);
+        }
+    }
+
+    /// Records the offset of the column value which was just written. Offsets are flushed to the
+    /// offsets file once there are `max_pending_offsets` of them. If it was the last column of the
+    /// row, call `finalize_row()`.
+    fn end_column(&mut self) -> Result<(), NippyJarError> {
+        // Last offset represents the size of the data file if no more data is to be
+        // appended. Otherwise, represents the offset of the next data item.
+        self.offsets.push(self.values.data_size);
 
-        if self.jar.columns == self.column {
+        if self.offsets.len() >= self.max_pending_offsets {
+            self.flush_pending_offsets()?;
+        }
+
+        if self.jar.columns == self.values.column {
             self.finalize_row();
         }
 
-        Ok(len)
+        Ok(())
     }
 
     /// Prunes rows from data and offsets file and updates its configuration on disk
@@ -318,34 +723,58 @@ impl<H: NippyJarHeader> NippyJarWriter<H> {
         self.data_file.get_ref().sync_all()?;
 
         self.offsets_file.seek(SeekFrom::End(0))?;
-        self.data_file.seek(SeekFrom::End(0))?;
+        self.values.data_size = self.data_file.seek(SeekFrom::End(0))?;
 
         self.jar.rows = self.jar.rows.saturating_sub(num_rows);
         if self.jar.rows == 0 {
             self.jar.max_row_size = 0;
         }
+        self.prune_checksums()?;
         self.jar.freeze_config()?;
 
         Ok(())
     }
 
+    /// Removes the checksums of pruned rows, both the ones in memory and on disk.
+    fn prune_checksums(&mut self) -> Result<(), NippyJarError> {
+        let Some(checksums_file) = &mut self.checksums_file else { return Ok(()) };
+
+        let rows = self.jar.rows as u64;
+        checksums_file.flush()?;
+        let checksummed_rows = checksums_file.get_ref().metadata()?.len() / CHECKSUM_SIZE_BYTES;
+        if rows >= checksummed_rows {
+            self.checksums.truncate(((rows - checksummed_rows) * CHECKSUM_SIZE_BYTES) as usize);
+        } else {
+            self.checksums.clear();
+            checksums_file.get_mut().set_len(rows * CHECKSUM_SIZE_BYTES)?;
+            checksums_file.get_ref().sync_all()?;
+            checksums_file.seek(SeekFrom::End(0))?;
+        }
+
+        Ok(())
+    }
+
     /// Updates [`NippyJar`] with the new row count and maximum uncompressed row size, while
     /// resetting internal fields.
     fn finalize_row(&mut self) {
-        self.jar.max_row_size = self.jar.max_row_size.max(self.uncompressed_row_size);
+        let (row_size, checksum) = self.values.finish_row();
+        self.jar.max_row_size = self.jar.max_row_size.max(row_size);
         self.jar.rows += 1;
-
-        self.tmp_buf.clear();
-        self.uncompressed_row_size = 0;
-        self.column = 0;
+        if let Some(checksum) = checksum {
+            self.checksums.extend_from_slice(&checksum.to_le_bytes());
+        }
     }
 
     /// Commits configuration and offsets to disk. It drains the internal offset list.
     pub fn commit(&mut self) -> Result<(), NippyJarError> {
         self.data_file.flush()?;
         self.data_file.get_ref().sync_all()?;
+        if self.evict_page_cache {
+            self.evict_data_file_pages()?;
+        }
 
         self.commit_offsets()?;
+        self.commit_checksums(true)?;
 
         // Flushes `max_row_size` and total `rows` to disk.
         self.jar.freeze_config()?;
@@ -354,6 +783,30 @@ impl<H: NippyJarHeader> NippyJarWriter<H> {
         Ok(())
     }
 
+    /// Advises the kernel that the pages of the data file won't be needed again. Only clean pages
+    /// are evicted, so it should be called after syncing the data file.
+    fn evict_data_file_pages(&self) -> Result<(), NippyJarError> {
+        #[cfg(target_os = "linux")]
+        {
+            use std::os::unix::io::AsRawFd;
+
+            // SAFETY: The descriptor is valid as long as `self.data_file` is alive.
+            let result = unsafe {
+                libc::posix_fadvise(
+                    self.data_file.get_ref().as_raw_fd(),
+                    0,
+                    0,
+                    libc::POSIX_FADV_DONTNEED,
+                )
+            };
+            if result != 0 {
+                return Err(std::io::Error::from_raw_os_error(result).into())
+            }
+        }
+
+        Ok(())
+    }
+
     /// Commits changes to the data file and offsets without synchronizing all data to disk.
     ///
     /// This function flushes the buffered data to the data file and commits the offsets,
@@ -363,6 +816,7 @@ impl<H: NippyJarHeader> NippyJarWriter<H> {
         self.data_file.flush()?;
 
         self.commit_offsets_without_sync_all()?;
+        self.commit_checksums(false)?;
 
         // Flushes `max_row_size` and total `rows` to disk.
         self.jar.freeze_config()?;
@@ -371,6 +825,26 @@ impl<H: NippyJarHeader> NippyJarWriter<H> {
         Ok(())
     }
 
+    /// Flushes the checksums of the appended rows to disk, if enabled.
+    fn commit_checksums(&mut self, sync_all: bool) -> Result<(), NippyJarError> {
+        if let Some(checksums_file) = &mut self.checksums_file {
+            checksums_file.write_all(&self.checksums)?;
+            checksums_file.flush()?;
+            if sync_all {
+                checksums_file.get_ref().sync_all()?;
+            }
+            self.checksums.clear();
+        }
+        Ok(())
+    }
+
+    /// Flushes the in-memory offsets to the offsets file, without syncing it or committing the
+    /// configuration. The data they point to has to be written to the data file first.
+    fn flush_pending_offsets(&mut self) -> Result<(), NippyJarError> {
+        self.data_file.flush()?;
+        self.commit_offsets_inner()
+    }
+
     /// Flushes offsets to disk.
     pub(crate) fn commit_offsets(&mut self) -> Result<(), NippyJarError> {
         self.commit_offsets_inner()?;
@@ -425,7 +899,7 @@ impl<H: NippyJarHeader> NippyJarWriter<H> {
     /// Returns the column index of the current checker instance.
     #[cfg(test)]
     pub const fn column(&self) -> usize {
-        self.column
+        self.values.column
     }
 
     /// Returns a reference to the offsets vector.
@@ -464,3 +938,95 @@ impl<H: NippyJarHeader> NippyJarWriter<H> {
         &self.jar
     }
 }
+
+/// Writes column values to a data sink, compressing them if their column is compressed, and keeps
+/// track of the row being written.
+///
+/// Shared by [`NippyJarWriter`] and [`NippyJar::freeze_into`], which only differ in where the data
+/// and offsets end up.
+#[derive(Debug)]
+pub(crate) struct ValueWriter {
+    /// Size of the written data, which is also the offset of the next value.
+    pub(crate) data_size: u64,
+    /// Column of the next value.
+    pub(crate) column: usize,
+    /// Used to find the maximum uncompressed size of a row in a jar.
+    uncompressed_row_size: usize,
+    /// Checksum of the row being written, if enabled.
+    row_hasher: Option<crc32fast::Hasher>,
+    /// Temporary buffer to reuse when compressing data.
+    tmp_buf: Vec<u8>,
+}
+
+impl ValueWriter {
+    /// Creates a [`ValueWriter`] appending to data of `data_size` bytes.
+    pub(crate) fn new(data_size: u64) -> Self {
+        Self {
+            data_size,
+            column: 0,
+            uncompressed_row_size: 0,
+            row_hasher: None,
+            tmp_buf: Vec::with_capacity(1_000_000),
+        }
+    }
+
+    /// Writes a value of the next column to `data`, compressing it if the column is compressed.
+    pub(crate) fn write_value<H: NippyJarHeader>(
+        &mut self,
+        jar: &NippyJar<H>,
+        value: &[u8],
+        data: &mut impl Write,
+    ) -> Result<(), NippyJarError> {
+        let Some(compression) = jar.column_compressor(self.column) else {
+            return self.write_stored_value(value.len(), value, data)
+        };
+
+        self.tmp_buf.clear();
+        compression.compress_column_to(self.column, value, &mut self.tmp_buf)?;
+
+        let tmp_buf = std::mem::take(&mut self.tmp_buf);
+        let result = self.write_stored_value(value.len(), &tmp_buf, data);
+        self.tmp_buf = tmp_buf;
+        result
+    }
+
+    /// Writes a value of the next column, as stored on disk, to `data`.
+    pub(crate) fn write_stored_value(
+        &mut self,
+        uncompressed_len: usize,
+        value: &[u8],
+        data: &mut impl Write,
+    ) -> Result<(), NippyJarError> {
+        data.write_all(value)?;
+        if let Some(row_hasher) = &mut self.row_hasher {
+            row_hasher.update(value);
+        }
+        self.data_size += value.len() as u64;
+
+        self.uncompressed_row_size += uncompressed_len;
+        self.column += 1;
+
+        Ok(())
+    }
+
+    /// Resets the row state once all of its columns have been written. Returns the uncompressed
+    /// size of the row, and its checksum if enabled.
+    pub(crate) fn finish_row(&mut self) -> (usize, Option<u32>) {
+        let row_size = std::mem::take(&mut self.uncompressed_row_size);
+        let checksum = self.row_hasher.as_mut().map(|hasher| std::mem::take(hasher).finalize());
+        self.column = 0;
+
+        (row_size, checksum)
+    }
+}
+
+/// Progress of a [`NippyJarWriter`], as reported by [`NippyJarWriter::append_rows_with_progress`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WriteProgress {
+    /// Number of complete rows in the jar, including the committed ones.
+    pub rows: usize,
+    /// Column of the current row which is written next.
+    pub column: usize,
+    /// Size of the data file in bytes, including uncommitted data.
+    pub data_size: u64,
+}